@@ -1,7 +1,340 @@
+use std::{env, fs::File, io::{self, BufWriter, Write}, path::Path};
+
+use gl_generator::{Api, Fallbacks, Generator, Profile, Registry};
+
+struct GearGenerator {
+    version: (u8, u8),
+    compatibility: bool,
+    trace: bool,
+    debug: bool,
+}
+
+impl Generator for GearGenerator {
+    fn write<W: Write>(&self, registry: &Registry, dest: &mut W) -> io::Result<()> {
+        writeln!(dest, "mod __gl_imports {{ pub use std::os::raw; }}")?;
+
+        writeln!(dest, "pub mod types {{")?;
+        writeln!(dest, "#![allow(non_camel_case_types, non_snake_case, dead_code, missing_copy_implementations)]")?;
+        gl_generator::generators::gen_types(registry.api, dest)?;
+        writeln!(dest, "}}")?;
+
+        writeln!(dest, "pub const TABLE_VERSION: (i32, i32) = ({}, {});", self.version.0, self.version.1)?;
+        writeln!(dest, "pub const TABLE_COMPATIBILITY: bool = {};", self.compatibility)?;
+
+        for enm in &registry.enums {
+            gl_generator::generators::gen_enum_item(enm, "types::", dest)?;
+        }
+
+        writeln!(dest, "pub struct GLFunctions {{")?;
+        for cmd in &registry.cmds {
+            writeln!(
+                dest,
+                "    pub(crate) {}: Proc<extern \"system\" fn({}) -> {}>,",
+                cmd.proto.ident,
+                gl_generator::generators::gen_parameters(cmd, true, true).join(", "),
+                cmd.proto.ty,
+            )?;
+        }
+        writeln!(dest, "}}")?;
+
+        writeln!(dest, "impl GLFunctions {{")?;
+
+        writeln!(dest, "    pub fn load_with<F: FnMut(&'static str) -> *const __gl_imports::raw::c_void>(mut loader: F) -> Self {{")?;
+        writeln!(dest, "        GLFunctions {{")?;
+        for cmd in &registry.cmds {
+            let fallbacks = registry.aliases.get(&cmd.proto.ident)
+                .map(|aliases| aliases.iter().map(|alias| format!("\"{}\"", symbol(alias))).collect::<Vec<_>>())
+                .unwrap_or_default();
+            writeln!(
+                dest,
+                "            {}: Proc::load_with(\"{}\", &[{}], &mut loader),",
+                cmd.proto.ident,
+                symbol(&cmd.proto.ident),
+                fallbacks.join(", "),
+            )?;
+        }
+        writeln!(dest, "        }}")?;
+        writeln!(dest, "    }}")?;
+
+        writeln!(dest, "    pub fn is_loaded(&self, name: &str) -> bool {{")?;
+        writeln!(dest, "        match name {{")?;
+        for cmd in &registry.cmds {
+            writeln!(dest, "            \"{}\" => self.{}.is_loaded(),", symbol(&cmd.proto.ident), cmd.proto.ident)?;
+        }
+        writeln!(dest, "            _ => false,")?;
+        writeln!(dest, "        }}")?;
+        writeln!(dest, "    }}")?;
+
+        writeln!(dest, "    pub fn missing(&self) -> Vec<&'static str> {{")?;
+        writeln!(dest, "        let procs = [")?;
+        for cmd in &registry.cmds {
+            writeln!(dest, "            (self.{0}.name(), self.{0}.is_loaded()),", cmd.proto.ident)?;
+        }
+        writeln!(dest, "        ];")?;
+        writeln!(dest, "        procs.iter().filter(|(_, loaded)| !loaded).map(|(name, _)| *name).collect()")?;
+        writeln!(dest, "    }}")?;
+
+        for cmd in &registry.cmds {
+            let args = gl_generator::generators::gen_parameters(cmd, true, false);
+
+            writeln!(
+                dest,
+                "    pub unsafe fn {}(&self, {}) -> Result<{}> {{",
+                cmd.proto.ident,
+                gl_generator::generators::gen_parameters(cmd, true, true).join(", "),
+                cmd.proto.ty,
+            )?;
+            writeln!(dest, "        let __proc = self.{}.get()?;", cmd.proto.ident)?;
+            if self.trace {
+                writeln!(dest, "        let __start = std::time::Instant::now();")?;
+            }
+            writeln!(dest, "        let __result = __proc({});", args.join(", "))?;
+            if self.trace {
+                writeln!(
+                    dest,
+                    "        log::trace!(target: \"gear::gl\", \"{}({}) -> {{:?}} in {{:?}}\", {}__result, __start.elapsed());",
+                    symbol(&cmd.proto.ident),
+                    vec!["{:?}"; args.len()].join(", "),
+                    args.iter().map(|arg| format!("{}, ", arg)).collect::<String>(),
+                )?;
+            }
+            if self.debug && cmd.proto.ident != "GetError" {
+                writeln!(dest, "        self.check_error(\"{}\")?;", symbol(&cmd.proto.ident))?;
+            }
+            writeln!(dest, "        Ok(__result)")?;
+            writeln!(dest, "    }}")?;
+        }
+
+        writeln!(dest, "}}")
+    }
+}
+
+// Recording stand-ins for every command, handed out by `mock::loader`.
+struct MockGenerator;
+
+impl Generator for MockGenerator {
+    fn write<W: Write>(&self, registry: &Registry, dest: &mut W) -> io::Result<()> {
+        writeln!(dest, "mod __gl_imports {{ pub use std::os::raw; }}")?;
+
+        for cmd in &registry.cmds {
+            let args = gl_generator::generators::gen_parameters(cmd, true, false)
+                .iter()
+                .map(|arg| format!("IntoMockArg::into_mock_arg({})", arg))
+                .collect::<Vec<_>>();
+
+            writeln!(
+                dest,
+                "extern \"system\" fn {}({}) -> {} {{",
+                symbol(&cmd.proto.ident),
+                gl_generator::generators::gen_parameters(cmd, true, true).join(", "),
+                cmd.proto.ty,
+            )?;
+            writeln!(dest, "    MockReturn::from_bits(record(\"{}\", vec![{}]))", symbol(&cmd.proto.ident), args.join(", "))?;
+            writeln!(dest, "}}")?;
+        }
+
+        writeln!(dest, "pub fn loader(name: &'static str) -> *const __gl_imports::raw::c_void {{")?;
+        writeln!(dest, "    match name {{")?;
+        for cmd in &registry.cmds {
+            writeln!(dest, "        \"{0}\" => {0} as *const __gl_imports::raw::c_void,", symbol(&cmd.proto.ident))?;
+        }
+        writeln!(dest, "        _ => std::ptr::null(),")?;
+        writeln!(dest, "    }}")?;
+        writeln!(dest, "}}")
+    }
+}
+
+fn symbol(ident: &str) -> String {
+    format!("gl{}", ident)
+}
+
 #[allow(non_snake_case)]
 fn main() {
-    let outDir = std::env::var("OUT_DIR").unwrap();
+    let outDir = env::var("OUT_DIR").unwrap();
+
+    let version = if env::var_os("CARGO_FEATURE_GL45").is_some() {
+        (4, 5)
+    } else if env::var_os("CARGO_FEATURE_GL41").is_some() {
+        (4, 1)
+    } else {
+        (3, 3)
+    };
+    let compatibility = env::var_os("CARGO_FEATURE_COMPATIBILITY").is_some();
+    let profile = if compatibility { Profile::Compatibility } else { Profile::Core };
+
+    let trace = env::var_os("CARGO_FEATURE_GL_TRACE").is_some();
+    let debug = env::var_os("CARGO_FEATURE_GL_DEBUG").is_some();
+    // Debug output is only core from 4.3 on; older contexts may still expose it as an extension.
+    let extensions: &[&str] = if debug { &["GL_KHR_debug"] } else { &[] };
+
+    let registry = Registry::new(Api::Gl, version, profile, Fallbacks::All, extensions);
+
+    let mut file = BufWriter::new(File::create(Path::new(&outDir).join("gl_bindings.rs")).unwrap());
+    registry.write_bindings(GearGenerator { version, compatibility, trace, debug }, &mut file).unwrap();
+
+    if env::var_os("CARGO_FEATURE_MOCK").is_some() {
+        let mut file = BufWriter::new(File::create(Path::new(&outDir).join("gl_mock.rs")).unwrap());
+        registry.write_bindings(MockGenerator, &mut file).unwrap();
+    }
+
+    #[cfg(feature = "header")]
+    write_header();
+
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "bundled")]
+    build_glfw();
+
+    #[cfg(not(feature = "bundled"))]
+    find_glfw();
+}
+
+#[cfg(not(feature = "bundled"))]
+fn find_glfw() {
+    if pkg_config::Config::new().atleast_version("3.3").probe("glfw3").is_ok() {
+        return;
+    }
+
+    // Without pkg-config (usually Windows) the library has to be on the linker's search path.
+    println!("cargo:warning=GLFW 3.3 was not found with pkg-config; enable the `bundled` feature to build it from source");
+    let name = if env::var("CARGO_CFG_TARGET_OS").unwrap() == "windows" { "glfw3" } else { "glfw" };
+    println!("cargo:rustc-link-lib=dylib={}", name);
+}
+
+// Compiles the GLFW 3.3.9 sources in vendor/glfw into a static library. On Linux and the BSDs the
+// `wayland` feature builds the Wayland backend instead of X11, and `osmesa` the null platform
+// instead of either; GLFW 3.3 supports one per build.
+#[cfg(feature = "bundled")]
+fn build_glfw() {
+    let root = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("vendor").join("glfw");
+    let src = root.join("src");
+    let target = env::var("CARGO_CFG_TARGET_OS").unwrap();
+
+    println!("cargo:rerun-if-changed={}", root.display());
+
+    let mut build = cc::Build::new();
+    build.include(root.join("include")).include(&src).warnings(false);
+
+    let mut sources = vec!["context.c", "init.c", "input.c", "monitor.c", "vulkan.c", "window.c"];
+    let mut libraries: Vec<&str> = Vec::new();
+
+    if target == "windows" {
+        build.define("_GLFW_WIN32", None);
+        sources.extend([
+            "win32_init.c", "win32_joystick.c", "win32_monitor.c", "win32_time.c", "win32_thread.c",
+            "win32_window.c", "wgl_context.c", "egl_context.c", "osmesa_context.c",
+        ]);
+        libraries.extend(["gdi32", "user32", "shell32"]);
+    } else if target == "macos" {
+        build.define("_GLFW_COCOA", None);
+        sources.extend([
+            "cocoa_init.m", "cocoa_joystick.m", "cocoa_monitor.m", "cocoa_window.m", "cocoa_time.c",
+            "posix_thread.c", "nsgl_context.m", "egl_context.c", "osmesa_context.c",
+        ]);
+        for framework in ["Cocoa", "IOKit", "CoreFoundation"] {
+            println!("cargo:rustc-link-lib=framework={}", framework);
+        }
+    } else if env::var_os("CARGO_FEATURE_OSMESA").is_some() {
+        // GLFW's null platform: no display server at all, contexts come from Mesa's OSMesa, which
+        // GLFW loads at runtime.
+        build.define("_GLFW_OSMESA", None);
+        sources.extend([
+            "null_init.c", "null_monitor.c", "null_window.c", "null_joystick.c", "posix_time.c",
+            "posix_thread.c", "osmesa_context.c",
+        ]);
+    } else {
+        sources.extend(["posix_time.c", "posix_thread.c", "xkb_unicode.c", "egl_context.c", "osmesa_context.c"]);
+        sources.push(if target == "linux" { "linux_joystick.c" } else { "null_joystick.c" });
+
+        if env::var_os("CARGO_FEATURE_WAYLAND").is_some() {
+            build.define("_GLFW_WAYLAND", None);
+            if target == "linux" {
+                build.define("HAVE_MEMFD_CREATE", None);
+            }
+            sources.extend(["wl_init.c", "wl_monitor.c", "wl_window.c"]);
+
+            for library in ["wayland-client", "wayland-cursor", "wayland-egl", "xkbcommon"] {
+                let library = pkg_config::probe_library(library)
+                    .unwrap_or_else(|error| panic!("the Wayland backend needs {}: {}", library, error));
+                build.includes(library.include_paths);
+            }
+            generate_wayland_protocols(&mut build);
+        } else {
+            build.define("_GLFW_X11", None);
+            sources.extend(["x11_init.c", "x11_monitor.c", "x11_window.c", "glx_context.c"]);
+
+            // Only libX11 is linked; GLFW loads the extension libraries at runtime but needs their
+            // headers (Xrandr, Xinerama, Xcursor, XInput2) to build.
+            let x11 = pkg_config::probe_library("x11").unwrap_or_else(|error| panic!("the X11 backend needs libX11: {}", error));
+            build.includes(x11.include_paths);
+        }
+    }
+
+    build.files(sources.iter().map(|source| src.join(source))).compile("glfw3");
+
+    for library in libraries {
+        println!("cargo:rustc-link-lib=dylib={}", library);
+    }
+}
+
+// GLFW's Wayland backend includes client code generated from the wayland-protocols XML files.
+#[cfg(feature = "bundled")]
+fn generate_wayland_protocols(build: &mut cc::Build) {
+    use std::process::Command;
+
+    const PROTOCOLS: [(&str, &str); 6] = [
+        ("stable/xdg-shell/xdg-shell.xml", "xdg-shell"),
+        ("unstable/xdg-decoration/xdg-decoration-unstable-v1.xml", "xdg-decoration"),
+        ("stable/viewporter/viewporter.xml", "viewporter"),
+        ("unstable/relative-pointer/relative-pointer-unstable-v1.xml", "relative-pointer-unstable-v1"),
+        ("unstable/pointer-constraints/pointer-constraints-unstable-v1.xml", "pointer-constraints-unstable-v1"),
+        ("unstable/idle-inhibit/idle-inhibit-unstable-v1.xml", "idle-inhibit-unstable-v1"),
+    ];
+
+    let out_dir = Path::new(&env::var("OUT_DIR").unwrap()).join("wayland");
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    let protocols = pkg_config::get_variable("wayland-protocols", "pkgdatadir")
+        .unwrap_or_else(|error| panic!("the Wayland backend needs wayland-protocols: {}", error));
+    let scanner = pkg_config::get_variable("wayland-scanner", "wayland_scanner")
+        .unwrap_or_else(|_| "wayland-scanner".to_string());
+
+    for (xml, name) in PROTOCOLS {
+        let xml = Path::new(&protocols).join(xml);
+        let header = out_dir.join(format!("wayland-{}-client-protocol.h", name));
+        let code = out_dir.join(format!("wayland-{}-client-protocol.c", name));
+
+        for (mode, output) in [("client-header", &header), ("private-code", &code)] {
+            let status = Command::new(&scanner)
+                .arg(mode)
+                .arg(&xml)
+                .arg(output)
+                .status()
+                .unwrap_or_else(|error| panic!("failed to run {}: {}", scanner, error));
+            assert!(status.success(), "{} {} {} failed", scanner, mode, xml.display());
+        }
+
+        build.file(code);
+    }
+
+    build.include(out_dir);
+}
+
+// Regenerates the checked-in C header from the exports in src/capi.rs. Only the files that make
+// up the C API are parsed; the rest of the crate declares GLFW's own functions and constants.
+#[cfg(feature = "header")]
+fn write_header() {
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file(Path::new(&crate_dir).join("cbindgen.toml")).unwrap();
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
 
-    println!("cargo:rustc-link-search=native={}", outDir);
-    println!("cargo:rustc-link-lib=dylib=glfw3");
-}
\ No newline at end of file
+    cbindgen::Builder::new()
+        .with_src(Path::new(&crate_dir).join("src").join("capi.rs"))
+        .with_src(Path::new(&crate_dir).join("src").join("error.rs"))
+        .with_config(config)
+        .generate()
+        .expect("failed to generate gear.h")
+        .write_to_file(Path::new(&crate_dir).join("include").join("gear.h"));
+}
@@ -0,0 +1,427 @@
+#ifndef GEAR_H
+#define GEAR_H
+
+/* Generated by cbindgen from src/capi.rs; build with `--features header` to regenerate. */
+
+#include <stddef.h>
+
+typedef struct GLFWwindow GLFWwindow;
+typedef struct GLFWmonitor GLFWmonitor;
+
+#define GEAR_VERSION_MAJOR 0
+
+#define GEAR_VERSION_MINOR 1
+
+#define GEAR_VERSION_PATCH 0
+
+#define GEAR_ABI_VERSION 1
+
+#define GEAR_EVENT_NONE 0
+
+#define GEAR_EVENT_RESIZED 1
+
+#define GEAR_EVENT_FRAMEBUFFER_RESIZED 2
+
+#define GEAR_EVENT_KEY 3
+
+#define GEAR_EVENT_CHAR 4
+
+#define GEAR_EVENT_CURSOR_MOVED 5
+
+#define GEAR_EVENT_CURSOR_ENTERED 6
+
+#define GEAR_EVENT_MOUSE_BUTTON 7
+
+#define GEAR_EVENT_SCROLL 8
+
+#define GEAR_EVENT_FOCUSED 9
+
+#define GEAR_EVENT_ICONIFIED 10
+
+#define GEAR_EVENT_CLOSE_REQUESTED 11
+
+#define GEAR_EVENT_FILE_DROP 12
+
+#define GEAR_EVENT_CONTENT_SCALE 13
+
+#define GEAR_BUFFER_ARRAY 0
+
+#define GEAR_BUFFER_ELEMENT_ARRAY 1
+
+#define GEAR_BUFFER_UNIFORM 2
+
+#define GEAR_USAGE_STATIC 0
+
+#define GEAR_USAGE_DYNAMIC 1
+
+#define GEAR_USAGE_STREAM 2
+
+#define GEAR_ATTRIB_FLOAT 0
+
+#define GEAR_ATTRIB_BYTE 1
+
+#define GEAR_ATTRIB_UNSIGNED_BYTE 2
+
+#define GEAR_ATTRIB_SHORT 3
+
+#define GEAR_ATTRIB_UNSIGNED_SHORT 4
+
+#define GEAR_ATTRIB_INT 5
+
+#define GEAR_ATTRIB_UNSIGNED_INT 6
+
+#define GEAR_INDEX_U8 0
+
+#define GEAR_INDEX_U16 1
+
+#define GEAR_INDEX_U32 2
+
+#define GEAR_PRIMITIVE_POINTS 0
+
+#define GEAR_PRIMITIVE_LINES 1
+
+#define GEAR_PRIMITIVE_LINE_STRIP 2
+
+#define GEAR_PRIMITIVE_LINE_LOOP 3
+
+#define GEAR_PRIMITIVE_TRIANGLES 4
+
+#define GEAR_PRIMITIVE_TRIANGLE_STRIP 5
+
+#define GEAR_PRIMITIVE_TRIANGLE_FAN 6
+
+#define GEAR_FORMAT_R8 0
+
+#define GEAR_FORMAT_RGB8 1
+
+#define GEAR_FORMAT_RGBA8 2
+
+#define GEAR_FORMAT_R32F 3
+
+#define GEAR_FORMAT_RGB32F 4
+
+#define GEAR_FORMAT_RGBA32F 5
+
+#define GEAR_FORMAT_DEPTH24 6
+
+#define GEAR_FORMAT_DEPTH32F 7
+
+#define GEAR_FORMAT_DEPTH24_STENCIL8 8
+
+#define GEAR_FILTER_NEAREST 0
+
+#define GEAR_FILTER_LINEAR 1
+
+#define GEAR_FILTER_NEAREST_MIPMAP_NEAREST 2
+
+#define GEAR_FILTER_LINEAR_MIPMAP_NEAREST 3
+
+#define GEAR_FILTER_NEAREST_MIPMAP_LINEAR 4
+
+#define GEAR_FILTER_LINEAR_MIPMAP_LINEAR 5
+
+#define GEAR_WRAP_REPEAT 0
+
+#define GEAR_WRAP_MIRRORED_REPEAT 1
+
+#define GEAR_WRAP_CLAMP_TO_EDGE 2
+
+#define GEAR_WRAP_CLAMP_TO_BORDER 3
+
+#define GEAR_ATTACHMENT_COLOR 0
+
+#define GEAR_ATTACHMENT_DEPTH 1
+
+#define GEAR_ATTACHMENT_STENCIL 2
+
+#define GEAR_ATTACHMENT_DEPTH_STENCIL 3
+
+#define GEAR_OK 0
+
+#define GEAR_ERROR_INIT 1
+
+#define GEAR_ERROR_WINDOW_CREATION 2
+
+#define GEAR_ERROR_INVALID_ARGUMENT 3
+
+#define GEAR_ERROR_MISSING_SYMBOL 4
+
+#define GEAR_ERROR_SHADER_COMPILE 5
+
+#define GEAR_ERROR_NO_CONTEXT 6
+
+#define GEAR_ERROR_IMAGE 7
+
+#define GEAR_ERROR_FRAMEBUFFER_INCOMPLETE 8
+
+#define GEAR_ERROR_GL 9
+
+typedef struct GearBuffer GearBuffer;
+
+typedef struct GearContext GearContext;
+
+typedef struct GearCursor GearCursor;
+
+typedef struct GearFramebuffer GearFramebuffer;
+
+typedef struct GearShader GearShader;
+
+typedef struct GearTexture GearTexture;
+
+typedef struct GearVertexArray GearVertexArray;
+
+typedef struct GearWindow GearWindow;
+
+typedef struct GearWindowConfig {
+  int width;
+  int height;
+  const char *title;
+  GLFWmonitor *monitor;
+  int fullscreen;
+  int context_version_major;
+  int context_version_minor;
+  int opengl_profile;
+  int samples;
+  int visible;
+  int resizable;
+  int decorated;
+  int context_api;
+  int debug_context;
+} GearWindowConfig;
+
+typedef struct GearEvent {
+  int kind;
+  int width;
+  int height;
+  int key;
+  int scancode;
+  int button;
+  int action;
+  int mods;
+  unsigned int codepoint;
+  int value;
+  double x;
+  double y;
+  int path_count;
+} GearEvent;
+
+typedef struct GearVertexAttribute {
+  unsigned int location;
+  int components;
+  int attrib_type;
+  int normalized;
+  size_t offset;
+} GearVertexAttribute;
+
+typedef struct GearGamepadState {
+  unsigned char buttons[15];
+  float axes[6];
+} GearGamepadState;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+void gear_get_version(int *major, int *minor, int *patch);
+
+int gear_abi_version(void);
+
+const char *gear_last_error_message(void);
+
+int run(const char *title);
+
+int run_with_config(const struct GearWindowConfig *config);
+
+struct GearWindowConfig gear_window_config_default(void);
+
+int gear_window_create(const struct GearWindowConfig *config, struct GearWindow **window);
+
+int gear_window_create_shared(const struct GearWindowConfig *config,
+                              struct GearWindow *share,
+                              struct GearWindow **window);
+
+void gear_window_destroy(struct GearWindow *window);
+
+GLFWwindow *gear_window_get_glfw_window(struct GearWindow *window);
+
+int gear_window_get_context(struct GearWindow *window, struct GearContext **context);
+
+int gear_window_make_current(struct GearWindow *window);
+
+int gear_window_set_swap_interval(struct GearWindow *window, int interval);
+
+int gear_window_get_framebuffer_size(struct GearWindow *window, int *width, int *height);
+
+int gear_window_frame_begin(struct GearWindow *window);
+
+int gear_window_frame_end(struct GearWindow *window);
+
+int gear_window_set_should_close(struct GearWindow *window, int value);
+
+int gear_window_read_pixels(struct GearWindow *window, unsigned char *pixels, size_t len);
+
+int gear_window_next_event(struct GearWindow *window, struct GearEvent *event);
+
+const char *gear_window_dropped_path(struct GearWindow *window, int index);
+
+int gear_window_is_key_down(struct GearWindow *window, int key);
+
+int gear_window_is_mouse_button_down(struct GearWindow *window, int button);
+
+int gear_window_get_cursor_pos(struct GearWindow *window, double *x, double *y);
+
+int gear_window_set_cursor_pos(struct GearWindow *window, double x, double y);
+
+int gear_window_set_cursor_mode(struct GearWindow *window, int mode);
+
+int gear_window_set_raw_mouse_motion(struct GearWindow *window, int enabled);
+
+int gear_window_set_cursor(struct GearWindow *window, const struct GearCursor *cursor);
+
+int gear_cursor_create_standard(int shape, struct GearCursor **cursor);
+
+int gear_cursor_create(int width,
+                       int height,
+                       const unsigned char *pixels,
+                       int hot_x,
+                       int hot_y,
+                       struct GearCursor **cursor);
+
+void gear_cursor_destroy(struct GearCursor *cursor);
+
+void gear_context_destroy(struct GearContext *context);
+
+int gear_context_make_current(struct GearContext *context);
+
+int gear_context_is_loaded(struct GearContext *context, const char *name);
+
+int gear_context_clear(struct GearContext *context, float r, float g, float b, float a);
+
+int gear_context_viewport(struct GearContext *context, int x, int y, int width, int height);
+
+int gear_buffer_create(struct GearContext *context,
+                       int target,
+                       int usage,
+                       const void *data,
+                       size_t size,
+                       struct GearBuffer **buffer);
+
+void gear_buffer_destroy(struct GearBuffer *buffer);
+
+int gear_buffer_upload(struct GearBuffer *buffer, const void *data, size_t size);
+
+int gear_buffer_update(struct GearBuffer *buffer, size_t offset, const void *data, size_t size);
+
+int gear_buffer_bind_base(struct GearBuffer *buffer, unsigned int index);
+
+int gear_shader_create(struct GearContext *context,
+                       const char *vertex,
+                       const char *fragment,
+                       const char *geometry,
+                       struct GearShader **shader);
+
+void gear_shader_destroy(struct GearShader *shader);
+
+int gear_shader_set_float(struct GearShader *shader,
+                          const char *name,
+                          const float *values,
+                          int count);
+
+int gear_shader_set_int(struct GearShader *shader, const char *name, const int *values, int count);
+
+int gear_shader_set_mat3(struct GearShader *shader, const char *name, const float *values);
+
+int gear_shader_set_mat4(struct GearShader *shader, const char *name, const float *values);
+
+int gear_vertex_array_create(struct GearContext *context, struct GearVertexArray **vertex_array);
+
+void gear_vertex_array_destroy(struct GearVertexArray *vertex_array);
+
+int gear_vertex_array_bind_buffer(struct GearVertexArray *vertex_array,
+                                  struct GearBuffer *buffer,
+                                  const struct GearVertexAttribute *attributes,
+                                  int count,
+                                  size_t stride,
+                                  unsigned int divisor);
+
+int gear_vertex_array_bind_index_buffer(struct GearVertexArray *vertex_array,
+                                        struct GearBuffer *buffer,
+                                        int index_type);
+
+int gear_draw_arrays(struct GearShader *shader,
+                     struct GearVertexArray *vertex_array,
+                     int primitive,
+                     size_t first,
+                     size_t count,
+                     size_t instances);
+
+int gear_draw_elements(struct GearShader *shader,
+                       struct GearVertexArray *vertex_array,
+                       int primitive,
+                       size_t first,
+                       size_t count,
+                       size_t instances);
+
+int gear_texture_create(struct GearContext *context,
+                        int width,
+                        int height,
+                        int format,
+                        const void *pixels,
+                        size_t size,
+                        struct GearTexture **texture);
+
+int gear_texture_load(struct GearContext *context, const char *path, struct GearTexture **texture);
+
+void gear_texture_destroy(struct GearTexture *texture);
+
+int gear_texture_get_size(struct GearTexture *texture, int *width, int *height);
+
+int gear_texture_sub_image(struct GearTexture *texture,
+                           int x,
+                           int y,
+                           int width,
+                           int height,
+                           const void *pixels,
+                           size_t size);
+
+int gear_texture_generate_mipmaps(struct GearTexture *texture);
+
+int gear_texture_set_filter(struct GearTexture *texture, int min, int mag);
+
+int gear_texture_set_wrap(struct GearTexture *texture, int s, int t);
+
+int gear_texture_bind(struct GearTexture *texture, unsigned int unit);
+
+int gear_framebuffer_create(struct GearContext *context,
+                            int width,
+                            int height,
+                            struct GearFramebuffer **framebuffer);
+
+void gear_framebuffer_destroy(struct GearFramebuffer *framebuffer);
+
+int gear_framebuffer_attach_texture(struct GearFramebuffer *framebuffer,
+                                    int attachment,
+                                    unsigned int index,
+                                    struct GearTexture *texture);
+
+int gear_framebuffer_check(struct GearFramebuffer *framebuffer);
+
+int gear_framebuffer_bind(struct GearFramebuffer *framebuffer);
+
+int gear_framebuffer_unbind(struct GearFramebuffer *framebuffer);
+
+int gear_joystick_present(int jid);
+
+int gear_joystick_is_gamepad(int jid);
+
+int gear_gamepad_get_state(int jid, struct GearGamepadState *state);
+
+int gear_gamepad_update_mappings(const char *mappings);
+
+void gear_set_joystick_callback(void (*callback)(int, int, void*), void *user_data);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GEAR_H */
@@ -0,0 +1,177 @@
+use std::{cell::Cell, ffi::c_void, marker::PhantomData, mem::size_of, rc::Rc};
+
+use bytemuck::Pod;
+
+use crate::{context::GlContext, error::*, gl, gl::types::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferTarget {
+    ArrayBuffer,
+    ElementArrayBuffer,
+    UniformBuffer,
+}
+
+impl BufferTarget {
+    pub fn gl(self) -> GLenum {
+        match self {
+            BufferTarget::ArrayBuffer => gl::ARRAY_BUFFER,
+            BufferTarget::ElementArrayBuffer => gl::ELEMENT_ARRAY_BUFFER,
+            BufferTarget::UniformBuffer => gl::UNIFORM_BUFFER,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferUsage {
+    StaticDraw,
+    DynamicDraw,
+    StreamDraw,
+}
+
+impl BufferUsage {
+    pub fn gl(self) -> GLenum {
+        match self {
+            BufferUsage::StaticDraw => gl::STATIC_DRAW,
+            BufferUsage::DynamicDraw => gl::DYNAMIC_DRAW,
+            BufferUsage::StreamDraw => gl::STREAM_DRAW,
+        }
+    }
+}
+
+/// A GL buffer holding elements of `T`. Its length is counted in elements, not bytes.
+pub struct Buffer<T: Pod> {
+    context: GlContext,
+    name: GLuint,
+    target: BufferTarget,
+    usage: BufferUsage,
+    len: Rc<Cell<usize>>,
+    _marker: PhantomData<T>,
+}
+
+// The current size of a buffer, shared with the vertex arrays reading from it so their vertex
+// and index counts follow later uploads.
+#[derive(Clone)]
+pub(crate) struct BufferSize {
+    len: Rc<Cell<usize>>,
+    element: usize,
+}
+
+impl BufferSize {
+    pub(crate) fn bytes(&self) -> usize {
+        self.len.get() * self.element
+    }
+}
+
+impl<T: Pod> Buffer<T> {
+    pub fn new(context: &GlContext, target: BufferTarget, usage: BufferUsage) -> Result<Self> {
+        let mut name: GLuint = 0;
+        unsafe { context.gl()?.GenBuffers(1, &mut name)? };
+
+        Ok(Buffer {
+            context: context.clone(),
+            name,
+            target,
+            usage,
+            len: Rc::default(),
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn from_slice(context: &GlContext, target: BufferTarget, usage: BufferUsage, data: &[T]) -> Result<Self> {
+        let mut buffer = Buffer::new(context, target, usage)?;
+        buffer.upload(data)?;
+        Ok(buffer)
+    }
+
+    pub fn context(&self) -> &GlContext {
+        &self.context
+    }
+
+    pub fn name(&self) -> GLuint {
+        self.name
+    }
+
+    pub fn target(&self) -> BufferTarget {
+        self.target
+    }
+
+    pub fn usage(&self) -> BufferUsage {
+        self.usage
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn size_bytes(&self) -> usize {
+        self.len() * size_of::<T>()
+    }
+
+    pub(crate) fn size(&self) -> BufferSize {
+        BufferSize { len: self.len.clone(), element: size_of::<T>() }
+    }
+
+    pub fn bind(&self) -> Result<()> {
+        unsafe { self.context.gl()?.BindBuffer(self.target.gl(), self.name) }
+    }
+
+    pub fn unbind(&self) -> Result<()> {
+        unsafe { self.context.gl()?.BindBuffer(self.target.gl(), 0) }
+    }
+
+    pub fn bind_base(&self, index: GLuint) -> Result<()> {
+        if self.target != BufferTarget::UniformBuffer {
+            return Err(GearError::InvalidArgument("only uniform buffers can be bound to an index".into()));
+        }
+
+        unsafe { self.context.gl()?.BindBufferBase(self.target.gl(), index, self.name) }
+    }
+
+    pub fn upload(&mut self, data: &[T]) -> Result<()> {
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+
+        self.bind()?;
+        unsafe {
+            self.context.gl()?.BufferData(
+                self.target.gl(),
+                bytes.len() as GLsizeiptr,
+                bytes.as_ptr() as *const c_void,
+                self.usage.gl(),
+            )?;
+        }
+
+        self.len.set(data.len());
+        Ok(())
+    }
+
+    pub fn update(&mut self, offset: usize, data: &[T]) -> Result<()> {
+        if offset.checked_add(data.len()).is_none_or(|end| end > self.len()) {
+            return Err(GearError::InvalidArgument(format!(
+                "buffer update of {} elements at {} is out of bounds for a buffer of {}",
+                data.len(), offset, self.len()
+            )));
+        }
+
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+
+        self.bind()?;
+        unsafe {
+            self.context.gl()?.BufferSubData(
+                self.target.gl(),
+                (offset * size_of::<T>()) as GLintptr,
+                bytes.len() as GLsizeiptr,
+                bytes.as_ptr() as *const c_void,
+            )
+        }
+    }
+}
+
+impl<T: Pod> Drop for Buffer<T> {
+    fn drop(&mut self) {
+        let _ = self.context.with_current(false, |gl| unsafe { gl.DeleteBuffers(1, &self.name) });
+    }
+}
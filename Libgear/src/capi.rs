@@ -0,0 +1,1151 @@
+// The C ABI. Everything here is declared in `include/gear.h`, which is generated from this file
+// with `cargo build --features header`; the generated header is checked in, so regenerate it
+// whenever a signature or a `#[repr(C)]` layout changes and bump the ABI version.
+//
+// Objects are handed out as opaque handles and released with their `*_destroy` function.
+// Functions that can fail return a GEAR_* status code and write their result through an out
+// pointer; `gear_last_error_message` describes the last failure on the calling thread.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::{ffi::*, path::Path, ptr::{null, null_mut}, slice};
+
+use crate::{
+    buffer::*, context::GlContext, draw::*, error::*, event::*, framebuffer::*, gamepad::*, gl, image::Image,
+    input::*, shader::ShaderProgram, texture::*, types::*, vertex::*, window::*,
+};
+
+pub const GEAR_VERSION_MAJOR: c_int = 0;
+pub const GEAR_VERSION_MINOR: c_int = 1;
+pub const GEAR_VERSION_PATCH: c_int = 0;
+
+// Bumped on every incompatible change to a signature or layout in `gear.h`. Hosts compare it with
+// `gear_abi_version()` to make sure the library they loaded matches the header they built against.
+pub const GEAR_ABI_VERSION: c_int = 1;
+
+pub const GEAR_EVENT_NONE: c_int = 0;
+pub const GEAR_EVENT_RESIZED: c_int = 1;
+pub const GEAR_EVENT_FRAMEBUFFER_RESIZED: c_int = 2;
+pub const GEAR_EVENT_KEY: c_int = 3;
+pub const GEAR_EVENT_CHAR: c_int = 4;
+pub const GEAR_EVENT_CURSOR_MOVED: c_int = 5;
+pub const GEAR_EVENT_CURSOR_ENTERED: c_int = 6;
+pub const GEAR_EVENT_MOUSE_BUTTON: c_int = 7;
+pub const GEAR_EVENT_SCROLL: c_int = 8;
+pub const GEAR_EVENT_FOCUSED: c_int = 9;
+pub const GEAR_EVENT_ICONIFIED: c_int = 10;
+pub const GEAR_EVENT_CLOSE_REQUESTED: c_int = 11;
+pub const GEAR_EVENT_FILE_DROP: c_int = 12;
+pub const GEAR_EVENT_CONTENT_SCALE: c_int = 13;
+
+pub const GEAR_BUFFER_ARRAY: c_int = 0;
+pub const GEAR_BUFFER_ELEMENT_ARRAY: c_int = 1;
+pub const GEAR_BUFFER_UNIFORM: c_int = 2;
+
+pub const GEAR_USAGE_STATIC: c_int = 0;
+pub const GEAR_USAGE_DYNAMIC: c_int = 1;
+pub const GEAR_USAGE_STREAM: c_int = 2;
+
+pub const GEAR_ATTRIB_FLOAT: c_int = 0;
+pub const GEAR_ATTRIB_BYTE: c_int = 1;
+pub const GEAR_ATTRIB_UNSIGNED_BYTE: c_int = 2;
+pub const GEAR_ATTRIB_SHORT: c_int = 3;
+pub const GEAR_ATTRIB_UNSIGNED_SHORT: c_int = 4;
+pub const GEAR_ATTRIB_INT: c_int = 5;
+pub const GEAR_ATTRIB_UNSIGNED_INT: c_int = 6;
+
+pub const GEAR_INDEX_U8: c_int = 0;
+pub const GEAR_INDEX_U16: c_int = 1;
+pub const GEAR_INDEX_U32: c_int = 2;
+
+pub const GEAR_PRIMITIVE_POINTS: c_int = 0;
+pub const GEAR_PRIMITIVE_LINES: c_int = 1;
+pub const GEAR_PRIMITIVE_LINE_STRIP: c_int = 2;
+pub const GEAR_PRIMITIVE_LINE_LOOP: c_int = 3;
+pub const GEAR_PRIMITIVE_TRIANGLES: c_int = 4;
+pub const GEAR_PRIMITIVE_TRIANGLE_STRIP: c_int = 5;
+pub const GEAR_PRIMITIVE_TRIANGLE_FAN: c_int = 6;
+
+pub const GEAR_FORMAT_R8: c_int = 0;
+pub const GEAR_FORMAT_RGB8: c_int = 1;
+pub const GEAR_FORMAT_RGBA8: c_int = 2;
+pub const GEAR_FORMAT_R32F: c_int = 3;
+pub const GEAR_FORMAT_RGB32F: c_int = 4;
+pub const GEAR_FORMAT_RGBA32F: c_int = 5;
+pub const GEAR_FORMAT_DEPTH24: c_int = 6;
+pub const GEAR_FORMAT_DEPTH32F: c_int = 7;
+pub const GEAR_FORMAT_DEPTH24_STENCIL8: c_int = 8;
+
+pub const GEAR_FILTER_NEAREST: c_int = 0;
+pub const GEAR_FILTER_LINEAR: c_int = 1;
+pub const GEAR_FILTER_NEAREST_MIPMAP_NEAREST: c_int = 2;
+pub const GEAR_FILTER_LINEAR_MIPMAP_NEAREST: c_int = 3;
+pub const GEAR_FILTER_NEAREST_MIPMAP_LINEAR: c_int = 4;
+pub const GEAR_FILTER_LINEAR_MIPMAP_LINEAR: c_int = 5;
+
+pub const GEAR_WRAP_REPEAT: c_int = 0;
+pub const GEAR_WRAP_MIRRORED_REPEAT: c_int = 1;
+pub const GEAR_WRAP_CLAMP_TO_EDGE: c_int = 2;
+pub const GEAR_WRAP_CLAMP_TO_BORDER: c_int = 3;
+
+pub const GEAR_ATTACHMENT_COLOR: c_int = 0;
+pub const GEAR_ATTACHMENT_DEPTH: c_int = 1;
+pub const GEAR_ATTACHMENT_STENCIL: c_int = 2;
+pub const GEAR_ATTACHMENT_DEPTH_STENCIL: c_int = 3;
+
+pub struct GearWindow {
+    window: Window,
+    dropped_paths: Vec<CString>,
+}
+
+pub struct GearContext(GlContext);
+
+// Buffers are untyped over the C ABI; the element type comes from the vertex attributes or the
+// index type they are bound with.
+pub struct GearBuffer(Buffer<u8>);
+
+pub struct GearShader(ShaderProgram);
+
+pub struct GearVertexArray(VertexArray);
+
+pub struct GearTexture(Texture2D);
+
+pub struct GearFramebuffer(Framebuffer);
+
+pub struct GearCursor(Cursor);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct GearWindowConfig {
+    pub width: c_int,
+    pub height: c_int,
+    pub title: *const c_char,
+    pub monitor: *mut GLFWmonitor,
+    pub fullscreen: c_int,
+    pub context_version_major: c_int,
+    pub context_version_minor: c_int,
+    pub opengl_profile: c_int,
+    pub samples: c_int,
+    pub visible: c_int,
+    pub resizable: c_int,
+    pub decorated: c_int,
+    pub context_api: c_int,
+    pub debug_context: c_int,
+}
+
+impl Default for GearWindowConfig {
+    fn default() -> Self {
+        let hints = WindowHints::default();
+
+        GearWindowConfig {
+            width: 500,
+            height: 500,
+            title: null(),
+            monitor: null_mut(),
+            fullscreen: False,
+            context_version_major: hints.context_version.0,
+            context_version_minor: hints.context_version.1,
+            opengl_profile: hints.profile.hint(),
+            samples: hints.samples,
+            visible: flag(hints.visible),
+            resizable: flag(hints.resizable),
+            decorated: flag(hints.decorated),
+            context_api: hints.context_api.hint(),
+            debug_context: flag(hints.debug_context),
+        }
+    }
+}
+
+impl From<&GearWindowConfig> for WindowConfig {
+    fn from(config: &GearWindowConfig) -> Self {
+        let title = if config.title.is_null() {
+            CString::default()
+        } else {
+            unsafe { CStr::from_ptr(config.title) }.to_owned()
+        };
+
+        let monitor = if !config.monitor.is_null() {
+            Some(Monitor::Handle(config.monitor))
+        } else if config.fullscreen != False {
+            Some(Monitor::Primary)
+        } else {
+            None
+        };
+
+        WindowConfig {
+            width: config.width,
+            height: config.height,
+            title,
+            monitor,
+            share: null_mut(),
+            hints: WindowHints {
+                context_version: (config.context_version_major, config.context_version_minor),
+                profile: Profile::from_hint(config.opengl_profile),
+                context_api: ContextApi::from_hint(config.context_api),
+                samples: config.samples,
+                visible: config.visible != False,
+                resizable: config.resizable != False,
+                decorated: config.decorated != False,
+                debug_context: config.debug_context != False,
+            },
+        }
+    }
+}
+
+// Flattened event for C hosts. Only the fields that belong to `kind` are meaningful; dropped
+// file paths are read separately with `gear_window_dropped_path`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GearEvent {
+    pub kind: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub key: c_int,
+    pub scancode: c_int,
+    pub button: c_int,
+    pub action: c_int,
+    pub mods: c_int,
+    pub codepoint: c_uint,
+    pub value: c_int,
+    pub x: c_double,
+    pub y: c_double,
+    pub path_count: c_int,
+}
+
+impl From<&Event> for GearEvent {
+    fn from(event: &Event) -> Self {
+        let mut raw = GearEvent { kind: event_kind(event), ..Default::default() };
+
+        match event {
+            Event::Resized { width, height } | Event::FramebufferResized { width, height } => {
+                raw.width = *width;
+                raw.height = *height;
+            }
+            Event::Key { key, scancode, action, mods } => {
+                raw.key = key.raw();
+                raw.scancode = *scancode;
+                raw.action = action.raw();
+                raw.mods = mods.raw();
+            }
+            Event::Char(character) => raw.codepoint = *character as c_uint,
+            Event::CursorMoved { x, y } | Event::Scroll { x, y } => {
+                raw.x = *x;
+                raw.y = *y;
+            }
+            Event::CursorEntered(value) | Event::Focused(value) | Event::Iconified(value) => {
+                raw.value = flag(*value);
+            }
+            Event::MouseButton { button, action, mods } => {
+                raw.button = button.raw();
+                raw.action = action.raw();
+                raw.mods = mods.raw();
+            }
+            Event::CloseRequested => {}
+            Event::FileDrop(paths) => raw.path_count = paths.len() as c_int,
+            Event::ContentScale { x, y } => {
+                raw.x = *x as c_double;
+                raw.y = *y as c_double;
+            }
+        }
+
+        raw
+    }
+}
+
+// One attribute of a vertex buffer; `attrib_type` is a GEAR_ATTRIB_* value and `offset` is in bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct GearVertexAttribute {
+    pub location: c_uint,
+    pub components: c_int,
+    pub attrib_type: c_int,
+    pub normalized: c_int,
+    pub offset: usize,
+}
+
+// Same layout as GLFWgamepadstate; buttons hold GLFW_PRESS or GLFW_RELEASE.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GearGamepadState {
+    pub buttons: [c_uchar; 15],
+    pub axes: [c_float; 6],
+}
+
+impl From<GamepadState> for GearGamepadState {
+    fn from(state: GamepadState) -> Self {
+        GearGamepadState { buttons: state.buttons, axes: state.axes }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn gear_get_version(major: *mut c_int, minor: *mut c_int, patch: *mut c_int) {
+    unsafe {
+        if let Some(major) = major.as_mut() {
+            *major = GEAR_VERSION_MAJOR;
+        }
+        if let Some(minor) = minor.as_mut() {
+            *minor = GEAR_VERSION_MINOR;
+        }
+        if let Some(patch) = patch.as_mut() {
+            *patch = GEAR_VERSION_PATCH;
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn gear_abi_version() -> c_int {
+    GEAR_ABI_VERSION
+}
+
+// Valid until the next failing call on the same thread; null if nothing has failed yet.
+#[no_mangle]
+pub extern "C" fn gear_last_error_message() -> *const c_char {
+    last_error_message()
+}
+
+#[no_mangle]
+pub extern "C" fn run(title: *const c_char) -> c_int {
+    let config = GearWindowConfig {
+        title,
+        ..Default::default()
+    };
+
+    status(crate::run_window(&WindowConfig::from(&config)))
+}
+
+#[no_mangle]
+pub extern "C" fn run_with_config(config: *const GearWindowConfig) -> c_int {
+    status(crate::run_window(&window_config(config)))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_config_default() -> GearWindowConfig {
+    GearWindowConfig::default()
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_create(config: *const GearWindowConfig, window: *mut *mut GearWindow) -> c_int {
+    gear_window_create_shared(config, null_mut(), window)
+}
+
+// Creates a window whose context shares objects with `share`; a null `share` behaves like
+// `gear_window_create`.
+#[no_mangle]
+pub extern "C" fn gear_window_create_shared(
+    config: *const GearWindowConfig,
+    share: *mut GearWindow,
+    window: *mut *mut GearWindow,
+) -> c_int {
+    create(window, || {
+        let mut config = window_config(config);
+        if let Some(share) = unsafe { share.as_ref() } {
+            config = config.share_with(&share.window);
+        }
+
+        Ok(GearWindow { window: Window::new(&config)?, dropped_paths: Vec::new() })
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_destroy(window: *mut GearWindow) {
+    destroy(window)
+}
+
+// The window's GLFW handle, for hosts that call GLFW directly.
+#[no_mangle]
+pub extern "C" fn gear_window_get_glfw_window(window: *mut GearWindow) -> *mut GLFWwindow {
+    match unsafe { window.as_ref() } {
+        Some(window) => window.window.handle(),
+        None => null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_get_context(window: *mut GearWindow, context: *mut *mut GearContext) -> c_int {
+    create(context, || Ok(GearContext(handle(window, "window")?.window.context().clone())))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_make_current(window: *mut GearWindow) -> c_int {
+    status(handle(window, "window").map(|window| window.window.make_current()))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_set_swap_interval(window: *mut GearWindow, interval: c_int) -> c_int {
+    status(handle(window, "window").map(|window| window.window.set_swap_interval(interval)))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_get_framebuffer_size(window: *mut GearWindow, width: *mut c_int, height: *mut c_int) -> c_int {
+    status(handle(window, "window").map(|window| {
+        let size = window.window.framebuffer_size();
+        write(width, size.0);
+        write(height, size.1);
+    }))
+}
+
+// Returns GLFW_TRUE while the window should stay open.
+#[no_mangle]
+pub extern "C" fn gear_window_frame_begin(window: *mut GearWindow) -> c_int {
+    match unsafe { window.as_ref() } {
+        Some(window) => flag(window.window.frame_begin()),
+        None => False,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_frame_end(window: *mut GearWindow) -> c_int {
+    status(handle(window, "window").map(|window| window.window.frame_end()))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_set_should_close(window: *mut GearWindow, value: c_int) -> c_int {
+    status(handle(window, "window").map(|window| window.window.set_should_close(value != False)))
+}
+
+// Copies the framebuffer as RGBA8, top row first; `len` has to be at least width * height * 4.
+#[no_mangle]
+pub extern "C" fn gear_window_read_pixels(window: *mut GearWindow, pixels: *mut c_uchar, len: usize) -> c_int {
+    status((|| {
+        let image = handle(window, "window")?.window.read_pixels()?;
+        if pixels.is_null() {
+            return Err(GearError::InvalidArgument("pixel buffer is null".into()));
+        }
+        if image.pixels().len() > len {
+            return Err(GearError::InvalidArgument(format!(
+                "pixel buffer of {} bytes is too small for {} bytes",
+                len, image.pixels().len()
+            )));
+        }
+
+        unsafe { std::ptr::copy_nonoverlapping(image.pixels().as_ptr(), pixels, image.pixels().len()) };
+        Ok(())
+    })())
+}
+
+// Returns GLFW_TRUE and fills `event` while events are queued, GLFW_FALSE once the queue is empty.
+#[no_mangle]
+pub extern "C" fn gear_window_next_event(window: *mut GearWindow, event: *mut GearEvent) -> c_int {
+    let (Some(window), Some(event)) = (unsafe { window.as_mut() }, unsafe { event.as_mut() }) else {
+        return False;
+    };
+
+    let Some(next) = window.window.next_event() else {
+        *event = GearEvent { kind: GEAR_EVENT_NONE, ..Default::default() };
+        return False;
+    };
+
+    if let Event::FileDrop(paths) = &next {
+        window.dropped_paths = paths
+            .iter()
+            .filter_map(|path| CString::new(path.to_string_lossy().into_owned()).ok())
+            .collect();
+    }
+
+    *event = GearEvent::from(&next);
+    True
+}
+
+// Paths of the last GEAR_EVENT_FILE_DROP returned by `gear_window_next_event`, valid until the next drop event is read.
+#[no_mangle]
+pub extern "C" fn gear_window_dropped_path(window: *mut GearWindow, index: c_int) -> *const c_char {
+    let Some(window) = (unsafe { window.as_ref() }) else { return null() };
+
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| window.dropped_paths.get(index))
+        .map_or(null(), |path| path.as_ptr())
+}
+
+// Key codes, mouse buttons, actions and modifier bits are GLFW's.
+#[no_mangle]
+pub extern "C" fn gear_window_is_key_down(window: *mut GearWindow, key: c_int) -> c_int {
+    match unsafe { window.as_ref() } {
+        Some(window) => flag(window.window.is_key_down(Key::from_raw(key))),
+        None => False,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_is_mouse_button_down(window: *mut GearWindow, button: c_int) -> c_int {
+    match (unsafe { window.as_ref() }, MouseButton::from_raw(button)) {
+        (Some(window), Some(button)) => flag(window.window.is_mouse_button_down(button)),
+        _ => False,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_get_cursor_pos(window: *mut GearWindow, x: *mut c_double, y: *mut c_double) -> c_int {
+    status(handle(window, "window").map(|window| {
+        let (cursor_x, cursor_y) = window.window.cursor_position();
+        write(x, cursor_x);
+        write(y, cursor_y);
+    }))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_set_cursor_pos(window: *mut GearWindow, x: c_double, y: c_double) -> c_int {
+    status(handle(window, "window").map(|window| window.window.set_cursor_position(x, y)))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_set_cursor_mode(window: *mut GearWindow, mode: c_int) -> c_int {
+    status(handle(window, "window").map(|window| window.window.set_cursor_mode(CursorMode::from_raw(mode))))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_window_set_raw_mouse_motion(window: *mut GearWindow, enabled: c_int) -> c_int {
+    status(handle(window, "window").and_then(|window| window.window.set_raw_mouse_motion(enabled != False)))
+}
+
+// A null `cursor` goes back to the default arrow.
+#[no_mangle]
+pub extern "C" fn gear_window_set_cursor(window: *mut GearWindow, cursor: *const GearCursor) -> c_int {
+    status(handle(window, "window").map(|window| {
+        window.window.set_cursor(unsafe { cursor.as_ref() }.map(|cursor| &cursor.0))
+    }))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_cursor_create_standard(shape: c_int, cursor: *mut *mut GearCursor) -> c_int {
+    create(cursor, || {
+        let shape = StandardCursor::from_raw(shape)
+            .ok_or_else(|| GearError::InvalidArgument(format!("unknown cursor shape {}", shape)))?;
+        Ok(GearCursor(Cursor::standard(shape)?))
+    })
+}
+
+// `pixels` holds width * height RGBA8 pixels, top row first.
+#[no_mangle]
+pub extern "C" fn gear_cursor_create(
+    width: c_int,
+    height: c_int,
+    pixels: *const c_uchar,
+    hot_x: c_int,
+    hot_y: c_int,
+    cursor: *mut *mut GearCursor,
+) -> c_int {
+    create(cursor, || {
+        let pixels = bytes(pixels as *const c_void, cursor_size(width, height)?, "cursor pixels")?;
+        Ok(GearCursor(Cursor::from_rgba(width, height, pixels, hot_x, hot_y)?))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn gear_cursor_destroy(cursor: *mut GearCursor) {
+    destroy(cursor)
+}
+
+#[no_mangle]
+pub extern "C" fn gear_context_destroy(context: *mut GearContext) {
+    destroy(context)
+}
+
+#[no_mangle]
+pub extern "C" fn gear_context_make_current(context: *mut GearContext) -> c_int {
+    status(handle(context, "context").and_then(|context| context.0.make_current()))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_context_is_loaded(context: *mut GearContext, name: *const c_char) -> c_int {
+    match (unsafe { context.as_ref() }, string(name, "function name")) {
+        (Some(context), Ok(name)) => flag(context.0.is_loaded(name)),
+        _ => False,
+    }
+}
+
+// Clears color and depth of whatever framebuffer is bound.
+#[no_mangle]
+pub extern "C" fn gear_context_clear(context: *mut GearContext, r: c_float, g: c_float, b: c_float, a: c_float) -> c_int {
+    status(handle(context, "context").and_then(|context| context.0.clear(BufferMask::COLOR | BufferMask::DEPTH, [r, g, b, a])))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_context_viewport(context: *mut GearContext, x: c_int, y: c_int, width: c_int, height: c_int) -> c_int {
+    status(handle(context, "context").and_then(|context| context.0.set_viewport(x, y, width, height)))
+}
+
+// `data` may be null to allocate `size` zeroed bytes.
+#[no_mangle]
+pub extern "C" fn gear_buffer_create(
+    context: *mut GearContext,
+    target: c_int,
+    usage: c_int,
+    data: *const c_void,
+    size: usize,
+    buffer: *mut *mut GearBuffer,
+) -> c_int {
+    create(buffer, || {
+        let context = &handle(context, "context")?.0;
+        let (target, usage) = (buffer_target(target)?, buffer_usage(usage)?);
+
+        let buffer = if data.is_null() {
+            Buffer::from_slice(context, target, usage, &vec![0u8; size])?
+        } else {
+            Buffer::from_slice(context, target, usage, bytes(data, size, "buffer data")?)?
+        };
+        Ok(GearBuffer(buffer))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn gear_buffer_destroy(buffer: *mut GearBuffer) {
+    destroy(buffer)
+}
+
+// Replaces the whole contents, reallocating if the size changes.
+#[no_mangle]
+pub extern "C" fn gear_buffer_upload(buffer: *mut GearBuffer, data: *const c_void, size: usize) -> c_int {
+    status((|| handle(buffer, "buffer")?.0.upload(bytes(data, size, "buffer data")?))())
+}
+
+#[no_mangle]
+pub extern "C" fn gear_buffer_update(buffer: *mut GearBuffer, offset: usize, data: *const c_void, size: usize) -> c_int {
+    status((|| handle(buffer, "buffer")?.0.update(offset, bytes(data, size, "buffer data")?))())
+}
+
+// Binds a GEAR_BUFFER_UNIFORM buffer to a uniform block binding point.
+#[no_mangle]
+pub extern "C" fn gear_buffer_bind_base(buffer: *mut GearBuffer, index: c_uint) -> c_int {
+    status(handle(buffer, "buffer").and_then(|buffer| buffer.0.bind_base(index)))
+}
+
+// `geometry` may be null.
+#[no_mangle]
+pub extern "C" fn gear_shader_create(
+    context: *mut GearContext,
+    vertex: *const c_char,
+    fragment: *const c_char,
+    geometry: *const c_char,
+    shader: *mut *mut GearShader,
+) -> c_int {
+    create(shader, || {
+        let context = &handle(context, "context")?.0;
+        let mut builder = ShaderProgram::builder()
+            .vertex(string(vertex, "vertex shader source")?)
+            .fragment(string(fragment, "fragment shader source")?);
+        if !geometry.is_null() {
+            builder = builder.geometry(string(geometry, "geometry shader source")?);
+        }
+
+        Ok(GearShader(builder.build(context)?))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn gear_shader_destroy(shader: *mut GearShader) {
+    destroy(shader)
+}
+
+// Sets a float, vec2, vec3 or vec4 uniform depending on `count`.
+#[no_mangle]
+pub extern "C" fn gear_shader_set_float(shader: *mut GearShader, name: *const c_char, values: *const c_float, count: c_int) -> c_int {
+    status((|| {
+        let (shader, name) = (&handle(shader, "shader")?.0, string(name, "uniform name")?);
+        match array::<f32>(values, count, "uniform values")? {
+            [x] => shader.set_uniform(name, *x),
+            [x, y] => shader.set_uniform(name, [*x, *y]),
+            [x, y, z] => shader.set_uniform(name, [*x, *y, *z]),
+            [x, y, z, w] => shader.set_uniform(name, [*x, *y, *z, *w]),
+            _ => Err(GearError::InvalidArgument(format!("uniforms have 1 to 4 components, got {}", count))),
+        }
+    })())
+}
+
+// Sets an int, ivec2, ivec3 or ivec4 uniform depending on `count`; samplers take the texture unit.
+#[no_mangle]
+pub extern "C" fn gear_shader_set_int(shader: *mut GearShader, name: *const c_char, values: *const c_int, count: c_int) -> c_int {
+    status((|| {
+        let (shader, name) = (&handle(shader, "shader")?.0, string(name, "uniform name")?);
+        match array::<i32>(values, count, "uniform values")? {
+            [x] => shader.set_uniform(name, *x),
+            [x, y] => shader.set_uniform(name, [*x, *y]),
+            [x, y, z] => shader.set_uniform(name, [*x, *y, *z]),
+            [x, y, z, w] => shader.set_uniform(name, [*x, *y, *z, *w]),
+            _ => Err(GearError::InvalidArgument(format!("uniforms have 1 to 4 components, got {}", count))),
+        }
+    })())
+}
+
+// `values` holds 9 floats in column-major order.
+#[no_mangle]
+pub extern "C" fn gear_shader_set_mat3(shader: *mut GearShader, name: *const c_char, values: *const c_float) -> c_int {
+    status((|| {
+        let (shader, name) = (&handle(shader, "shader")?.0, string(name, "uniform name")?);
+        let matrix: &[[f32; 3]] = bytemuck::cast_slice(array::<f32>(values, 9, "matrix")?);
+        shader.set_uniform(name, [matrix[0], matrix[1], matrix[2]])
+    })())
+}
+
+// `values` holds 16 floats in column-major order.
+#[no_mangle]
+pub extern "C" fn gear_shader_set_mat4(shader: *mut GearShader, name: *const c_char, values: *const c_float) -> c_int {
+    status((|| {
+        let (shader, name) = (&handle(shader, "shader")?.0, string(name, "uniform name")?);
+        let matrix: &[[f32; 4]] = bytemuck::cast_slice(array::<f32>(values, 16, "matrix")?);
+        shader.set_uniform(name, [matrix[0], matrix[1], matrix[2], matrix[3]])
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn gear_vertex_array_create(context: *mut GearContext, vertex_array: *mut *mut GearVertexArray) -> c_int {
+    create(vertex_array, || Ok(GearVertexArray(VertexArray::new(&handle(context, "context")?.0)?)))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_vertex_array_destroy(vertex_array: *mut GearVertexArray) {
+    destroy(vertex_array)
+}
+
+// Reads `count` attributes from `buffer`, `stride` bytes apart; a non-zero `divisor` makes them
+// per instance.
+#[no_mangle]
+pub extern "C" fn gear_vertex_array_bind_buffer(
+    vertex_array: *mut GearVertexArray,
+    buffer: *mut GearBuffer,
+    attributes: *const GearVertexAttribute,
+    count: c_int,
+    stride: usize,
+    divisor: c_uint,
+) -> c_int {
+    status((|| {
+        let vertex_array = &mut handle(vertex_array, "vertex array")?.0;
+        let buffer = &handle(buffer, "buffer")?.0;
+
+        let mut layout = VertexLayout::new();
+        for attribute in array(attributes, count, "vertex attributes")? {
+            let format = (attrib_type(attribute.attrib_type)?, attribute.components, attribute.normalized != False);
+            layout = layout.field(attribute.location, attribute.offset, format);
+        }
+
+        vertex_array.bind_buffer(buffer, &layout.stride(stride).divisor(divisor))
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn gear_vertex_array_bind_index_buffer(
+    vertex_array: *mut GearVertexArray,
+    buffer: *mut GearBuffer,
+    index_type: c_int,
+) -> c_int {
+    status((|| {
+        let vertex_array = &mut handle(vertex_array, "vertex array")?.0;
+        vertex_array.attach_indices(&handle(buffer, "buffer")?.0, self::index_type(index_type)?)
+    })())
+}
+
+// Draws `count` vertices starting at `first`; `instances` of 1 is a plain draw call.
+#[no_mangle]
+pub extern "C" fn gear_draw_arrays(
+    shader: *mut GearShader,
+    vertex_array: *mut GearVertexArray,
+    primitive: c_int,
+    first: usize,
+    count: usize,
+    instances: usize,
+) -> c_int {
+    status((|| {
+        let (shader, vertex_array) = (&handle(shader, "shader")?.0, &handle(vertex_array, "vertex array")?.0);
+        let range = first..first.saturating_add(count);
+        draw_arrays_instanced(shader, vertex_array, self::primitive(primitive)?, range, instances)
+    })())
+}
+
+// Draws `count` indices starting at index `first` of the bound index buffer.
+#[no_mangle]
+pub extern "C" fn gear_draw_elements(
+    shader: *mut GearShader,
+    vertex_array: *mut GearVertexArray,
+    primitive: c_int,
+    first: usize,
+    count: usize,
+    instances: usize,
+) -> c_int {
+    status((|| {
+        let (shader, vertex_array) = (&handle(shader, "shader")?.0, &handle(vertex_array, "vertex array")?.0);
+        let range = first..first.saturating_add(count);
+        draw_elements_instanced(shader, vertex_array, self::primitive(primitive)?, range, instances)
+    })())
+}
+
+// `pixels` may be null to leave the texture uninitialized; otherwise it holds `size` bytes of
+// tightly packed rows, bottom row first.
+#[no_mangle]
+pub extern "C" fn gear_texture_create(
+    context: *mut GearContext,
+    width: c_int,
+    height: c_int,
+    format: c_int,
+    pixels: *const c_void,
+    size: usize,
+    texture: *mut *mut GearTexture,
+) -> c_int {
+    create(texture, || {
+        let context = &handle(context, "context")?.0;
+        let format = pixel_format(format)?;
+
+        let texture = if pixels.is_null() {
+            Texture2D::new(context, width, height, format)?
+        } else {
+            Texture2D::from_pixels(context, width, height, format, bytes(pixels, size, "pixels")?)?
+        };
+        Ok(GearTexture(texture))
+    })
+}
+
+// Decodes a PNG, JPEG, BMP or TGA file into a new texture.
+#[no_mangle]
+pub extern "C" fn gear_texture_load(context: *mut GearContext, path: *const c_char, texture: *mut *mut GearTexture) -> c_int {
+    create(texture, || {
+        let context = &handle(context, "context")?.0;
+        let image = Image::load(Path::new(string(path, "path")?))?;
+        Ok(GearTexture(Texture2D::from_image(context, &image)?))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn gear_texture_destroy(texture: *mut GearTexture) {
+    destroy(texture)
+}
+
+#[no_mangle]
+pub extern "C" fn gear_texture_get_size(texture: *mut GearTexture, width: *mut c_int, height: *mut c_int) -> c_int {
+    status(handle(texture, "texture").map(|texture| {
+        write(width, texture.0.width());
+        write(height, texture.0.height());
+    }))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_texture_sub_image(
+    texture: *mut GearTexture,
+    x: c_int,
+    y: c_int,
+    width: c_int,
+    height: c_int,
+    pixels: *const c_void,
+    size: usize,
+) -> c_int {
+    status((|| handle(texture, "texture")?.0.sub_image(x, y, width, height, bytes(pixels, size, "pixels")?))())
+}
+
+#[no_mangle]
+pub extern "C" fn gear_texture_generate_mipmaps(texture: *mut GearTexture) -> c_int {
+    status(handle(texture, "texture").and_then(|texture| texture.0.generate_mipmaps()))
+}
+
+// `min` takes any GEAR_FILTER_* value, `mag` only GEAR_FILTER_NEAREST or GEAR_FILTER_LINEAR.
+#[no_mangle]
+pub extern "C" fn gear_texture_set_filter(texture: *mut GearTexture, min: c_int, mag: c_int) -> c_int {
+    status((|| {
+        let texture = &mut handle(texture, "texture")?.0;
+        texture.set_min_filter(min_filter(min)?)?;
+        texture.set_mag_filter(mag_filter(mag)?)
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn gear_texture_set_wrap(texture: *mut GearTexture, s: c_int, t: c_int) -> c_int {
+    status((|| handle(texture, "texture")?.0.set_wrap(wrap_mode(s)?, wrap_mode(t)?))())
+}
+
+// Binds to texture unit `unit` (below 47, which is reserved for setup); pass the same unit to
+// the sampler with `gear_shader_set_int`.
+#[no_mangle]
+pub extern "C" fn gear_texture_bind(texture: *mut GearTexture, unit: c_uint) -> c_int {
+    status(handle(texture, "texture").and_then(|texture| texture.0.bind(unit)).map(drop))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_framebuffer_create(
+    context: *mut GearContext,
+    width: c_int,
+    height: c_int,
+    framebuffer: *mut *mut GearFramebuffer,
+) -> c_int {
+    create(framebuffer, || Ok(GearFramebuffer(Framebuffer::new(&handle(context, "context")?.0, width, height)?)))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_framebuffer_destroy(framebuffer: *mut GearFramebuffer) {
+    destroy(framebuffer)
+}
+
+// `index` selects the color attachment and is ignored for the others. The texture has to outlive
+// the framebuffer.
+#[no_mangle]
+pub extern "C" fn gear_framebuffer_attach_texture(
+    framebuffer: *mut GearFramebuffer,
+    attachment: c_int,
+    index: c_uint,
+    texture: *mut GearTexture,
+) -> c_int {
+    status((|| {
+        let framebuffer = &mut handle(framebuffer, "framebuffer")?.0;
+        framebuffer.attach_texture(self::attachment(attachment, index)?, &handle(texture, "texture")?.0)
+    })())
+}
+
+// Returns GEAR_ERROR_FRAMEBUFFER_INCOMPLETE with the reason in `gear_last_error_message`.
+#[no_mangle]
+pub extern "C" fn gear_framebuffer_check(framebuffer: *mut GearFramebuffer) -> c_int {
+    status(handle(framebuffer, "framebuffer").and_then(|framebuffer| framebuffer.0.check()))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_framebuffer_bind(framebuffer: *mut GearFramebuffer) -> c_int {
+    status(handle(framebuffer, "framebuffer").and_then(|framebuffer| framebuffer.0.bind()))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_framebuffer_unbind(framebuffer: *mut GearFramebuffer) -> c_int {
+    status(handle(framebuffer, "framebuffer").and_then(|framebuffer| framebuffer.0.unbind()))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_joystick_present(jid: c_int) -> c_int {
+    flag(Joystick::new(jid).is_some_and(|joystick| joystick.is_present()))
+}
+
+#[no_mangle]
+pub extern "C" fn gear_joystick_is_gamepad(jid: c_int) -> c_int {
+    flag(Joystick::new(jid).is_some_and(|joystick| joystick.is_gamepad()))
+}
+
+// Returns GLFW_FALSE if the joystick is missing or has no gamepad mapping.
+#[no_mangle]
+pub extern "C" fn gear_gamepad_get_state(jid: c_int, state: *mut GearGamepadState) -> c_int {
+    match (Joystick::new(jid).and_then(|joystick| joystick.gamepad_state()), unsafe { state.as_mut() }) {
+        (Some(current), Some(state)) => {
+            *state = GearGamepadState::from(current);
+            True
+        }
+        _ => False,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn gear_gamepad_update_mappings(mappings: *const c_char) -> c_int {
+    status(string(mappings, "gamepad mappings").and_then(update_gamepad_mappings))
+}
+
+struct UserData(*mut c_void);
+
+// The pointer is only handed back to the host's callback; the host owns its thread safety.
+unsafe impl Send for UserData {}
+
+impl UserData {
+    fn get(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+// `connected` is GLFW_TRUE when the joystick was plugged in. A null callback removes it.
+#[no_mangle]
+pub extern "C" fn gear_set_joystick_callback(
+    callback: Option<extern "C" fn(c_int, c_int, *mut c_void)>,
+    user_data: *mut c_void,
+) {
+    match callback {
+        Some(callback) => {
+            let user_data = UserData(user_data);
+            set_joystick_callback(move |joystick, event| {
+                callback(joystick.id(), flag(event == JoystickEvent::Connected), user_data.get())
+            });
+        }
+        None => clear_joystick_callback(),
+    }
+}
+
+fn window_config(config: *const GearWindowConfig) -> WindowConfig {
+    match unsafe { config.as_ref() } {
+        Some(config) => WindowConfig::from(config),
+        None => WindowConfig::default(),
+    }
+}
+
+fn event_kind(event: &Event) -> c_int {
+    match event {
+        Event::Resized { .. } => GEAR_EVENT_RESIZED,
+        Event::FramebufferResized { .. } => GEAR_EVENT_FRAMEBUFFER_RESIZED,
+        Event::Key { .. } => GEAR_EVENT_KEY,
+        Event::Char(_) => GEAR_EVENT_CHAR,
+        Event::CursorMoved { .. } => GEAR_EVENT_CURSOR_MOVED,
+        Event::CursorEntered(_) => GEAR_EVENT_CURSOR_ENTERED,
+        Event::MouseButton { .. } => GEAR_EVENT_MOUSE_BUTTON,
+        Event::Scroll { .. } => GEAR_EVENT_SCROLL,
+        Event::Focused(_) => GEAR_EVENT_FOCUSED,
+        Event::Iconified(_) => GEAR_EVENT_ICONIFIED,
+        Event::CloseRequested => GEAR_EVENT_CLOSE_REQUESTED,
+        Event::FileDrop(_) => GEAR_EVENT_FILE_DROP,
+        Event::ContentScale { .. } => GEAR_EVENT_CONTENT_SCALE,
+    }
+}
+
+fn buffer_target(target: c_int) -> Result<BufferTarget> {
+    match target {
+        GEAR_BUFFER_ARRAY => Ok(BufferTarget::ArrayBuffer),
+        GEAR_BUFFER_ELEMENT_ARRAY => Ok(BufferTarget::ElementArrayBuffer),
+        GEAR_BUFFER_UNIFORM => Ok(BufferTarget::UniformBuffer),
+        _ => Err(unknown("buffer target", target)),
+    }
+}
+
+fn buffer_usage(usage: c_int) -> Result<BufferUsage> {
+    match usage {
+        GEAR_USAGE_STATIC => Ok(BufferUsage::StaticDraw),
+        GEAR_USAGE_DYNAMIC => Ok(BufferUsage::DynamicDraw),
+        GEAR_USAGE_STREAM => Ok(BufferUsage::StreamDraw),
+        _ => Err(unknown("buffer usage", usage)),
+    }
+}
+
+fn attrib_type(ty: c_int) -> Result<AttribType> {
+    match ty {
+        GEAR_ATTRIB_FLOAT => Ok(AttribType::Float),
+        GEAR_ATTRIB_BYTE => Ok(AttribType::Byte),
+        GEAR_ATTRIB_UNSIGNED_BYTE => Ok(AttribType::UnsignedByte),
+        GEAR_ATTRIB_SHORT => Ok(AttribType::Short),
+        GEAR_ATTRIB_UNSIGNED_SHORT => Ok(AttribType::UnsignedShort),
+        GEAR_ATTRIB_INT => Ok(AttribType::Int),
+        GEAR_ATTRIB_UNSIGNED_INT => Ok(AttribType::UnsignedInt),
+        _ => Err(unknown("attribute type", ty)),
+    }
+}
+
+fn index_type(ty: c_int) -> Result<gl::types::GLenum> {
+    match ty {
+        GEAR_INDEX_U8 => Ok(gl::UNSIGNED_BYTE),
+        GEAR_INDEX_U16 => Ok(gl::UNSIGNED_SHORT),
+        GEAR_INDEX_U32 => Ok(gl::UNSIGNED_INT),
+        _ => Err(unknown("index type", ty)),
+    }
+}
+
+fn primitive(primitive: c_int) -> Result<Primitive> {
+    match primitive {
+        GEAR_PRIMITIVE_POINTS => Ok(Primitive::Points),
+        GEAR_PRIMITIVE_LINES => Ok(Primitive::Lines),
+        GEAR_PRIMITIVE_LINE_STRIP => Ok(Primitive::LineStrip),
+        GEAR_PRIMITIVE_LINE_LOOP => Ok(Primitive::LineLoop),
+        GEAR_PRIMITIVE_TRIANGLES => Ok(Primitive::Triangles),
+        GEAR_PRIMITIVE_TRIANGLE_STRIP => Ok(Primitive::TriangleStrip),
+        GEAR_PRIMITIVE_TRIANGLE_FAN => Ok(Primitive::TriangleFan),
+        _ => Err(unknown("primitive", primitive)),
+    }
+}
+
+fn pixel_format(format: c_int) -> Result<PixelFormat> {
+    match format {
+        GEAR_FORMAT_R8 => Ok(PixelFormat::R8),
+        GEAR_FORMAT_RGB8 => Ok(PixelFormat::Rgb8),
+        GEAR_FORMAT_RGBA8 => Ok(PixelFormat::Rgba8),
+        GEAR_FORMAT_R32F => Ok(PixelFormat::R32F),
+        GEAR_FORMAT_RGB32F => Ok(PixelFormat::Rgb32F),
+        GEAR_FORMAT_RGBA32F => Ok(PixelFormat::Rgba32F),
+        GEAR_FORMAT_DEPTH24 => Ok(PixelFormat::Depth24),
+        GEAR_FORMAT_DEPTH32F => Ok(PixelFormat::Depth32F),
+        GEAR_FORMAT_DEPTH24_STENCIL8 => Ok(PixelFormat::Depth24Stencil8),
+        _ => Err(unknown("pixel format", format)),
+    }
+}
+
+fn min_filter(filter: c_int) -> Result<MinFilter> {
+    match filter {
+        GEAR_FILTER_NEAREST => Ok(MinFilter::Nearest),
+        GEAR_FILTER_LINEAR => Ok(MinFilter::Linear),
+        GEAR_FILTER_NEAREST_MIPMAP_NEAREST => Ok(MinFilter::NearestMipmapNearest),
+        GEAR_FILTER_LINEAR_MIPMAP_NEAREST => Ok(MinFilter::LinearMipmapNearest),
+        GEAR_FILTER_NEAREST_MIPMAP_LINEAR => Ok(MinFilter::NearestMipmapLinear),
+        GEAR_FILTER_LINEAR_MIPMAP_LINEAR => Ok(MinFilter::LinearMipmapLinear),
+        _ => Err(unknown("minification filter", filter)),
+    }
+}
+
+fn mag_filter(filter: c_int) -> Result<MagFilter> {
+    match filter {
+        GEAR_FILTER_NEAREST => Ok(MagFilter::Nearest),
+        GEAR_FILTER_LINEAR => Ok(MagFilter::Linear),
+        _ => Err(unknown("magnification filter", filter)),
+    }
+}
+
+fn wrap_mode(mode: c_int) -> Result<WrapMode> {
+    match mode {
+        GEAR_WRAP_REPEAT => Ok(WrapMode::Repeat),
+        GEAR_WRAP_MIRRORED_REPEAT => Ok(WrapMode::MirroredRepeat),
+        GEAR_WRAP_CLAMP_TO_EDGE => Ok(WrapMode::ClampToEdge),
+        GEAR_WRAP_CLAMP_TO_BORDER => Ok(WrapMode::ClampToBorder),
+        _ => Err(unknown("wrap mode", mode)),
+    }
+}
+
+fn attachment(attachment: c_int, index: c_uint) -> Result<Attachment> {
+    match attachment {
+        GEAR_ATTACHMENT_COLOR => Ok(Attachment::Color(index)),
+        GEAR_ATTACHMENT_DEPTH => Ok(Attachment::Depth),
+        GEAR_ATTACHMENT_STENCIL => Ok(Attachment::Stencil),
+        GEAR_ATTACHMENT_DEPTH_STENCIL => Ok(Attachment::DepthStencil),
+        _ => Err(unknown("attachment", attachment)),
+    }
+}
+
+fn unknown(what: &str, value: c_int) -> GearError {
+    GearError::InvalidArgument(format!("unknown {} {}", what, value))
+}
+
+fn flag(value: bool) -> c_int {
+    if value { True } else { False }
+}
+
+fn handle<'a, T>(pointer: *mut T, what: &str) -> Result<&'a mut T> {
+    unsafe { pointer.as_mut() }.ok_or_else(|| GearError::InvalidArgument(format!("{} is null", what)))
+}
+
+fn string<'a>(pointer: *const c_char, what: &str) -> Result<&'a str> {
+    if pointer.is_null() {
+        return Err(GearError::InvalidArgument(format!("{} is null", what)));
+    }
+
+    unsafe { CStr::from_ptr(pointer) }
+        .to_str()
+        .map_err(|_| GearError::InvalidArgument(format!("{} is not valid UTF-8", what)))
+}
+
+// A null pointer is only accepted for an empty slice.
+fn array<'a, T>(pointer: *const T, count: impl TryInto<usize>, what: &str) -> Result<&'a [T]> {
+    let count = count.try_into().map_err(|_| GearError::InvalidArgument(format!("{} count is negative", what)))?;
+    if count == 0 {
+        return Ok(&[]);
+    }
+    if pointer.is_null() {
+        return Err(GearError::InvalidArgument(format!("{} is null", what)));
+    }
+
+    Ok(unsafe { slice::from_raw_parts(pointer, count) })
+}
+
+fn bytes<'a>(pointer: *const c_void, size: usize, what: &str) -> Result<&'a [u8]> {
+    array(pointer as *const u8, size, what)
+}
+
+fn write<T>(pointer: *mut T, value: T) {
+    if let Some(pointer) = unsafe { pointer.as_mut() } {
+        *pointer = value;
+    }
+}
+
+// Writes the new handle through `out`, or null when `f` fails.
+fn create<T>(out: *mut *mut T, f: impl FnOnce() -> Result<T>) -> c_int {
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return status(Err(GearError::InvalidArgument("handle out pointer is null".into())));
+    };
+
+    *out = null_mut();
+    status(f().map(|value| *out = Box::into_raw(Box::new(value))))
+}
+
+fn destroy<T>(pointer: *mut T) {
+    if !pointer.is_null() {
+        drop(unsafe { Box::from_raw(pointer) });
+    }
+}
@@ -0,0 +1,187 @@
+use std::{cell::{Cell, RefCell}, ffi::{c_int, c_void}, rc::Rc};
+
+use crate::{error::*, framebuffer::BufferMask, gl::GLFunctions, types::*};
+use super::*;
+
+// Windows whose contexts were created sharing objects with each other.
+#[derive(Default)]
+struct ShareGroup {
+    windows: RefCell<Vec<*mut GLFWwindow>>,
+}
+
+impl ShareGroup {
+    fn contains(&self, window: *mut GLFWwindow) -> bool {
+        self.windows.borrow().contains(&window)
+    }
+
+    fn first(&self) -> Option<*mut GLFWwindow> {
+        self.windows.borrow().first().copied()
+    }
+}
+
+struct ContextInner {
+    window: Cell<*mut GLFWwindow>,
+    group: Rc<ShareGroup>,
+    functions: GLFunctions,
+    windowless: bool,
+}
+
+/// Function table of one window's context. It is reference counted with `Rc`, so it (and every
+/// resource holding a clone) stays on the thread that created the window.
+#[derive(Clone)]
+pub struct GlContext(Rc<ContextInner>);
+
+impl GlContext {
+    // Makes `window` current and loads the function table from it.
+    pub(crate) fn load(window: *mut GLFWwindow, share: Option<&GlContext>) -> Self {
+        unsafe { glfwMakeContextCurrent(window) };
+
+        let functions = GLFunctions::load_with(glfw_loader);
+        for name in functions.missing() {
+            log::warn!("OpenGL function {} could not be loaded", name);
+        }
+        #[cfg(feature = "gl-debug")]
+        if has_debug_output(window) {
+            functions.install_debug_callback();
+        } else {
+            log::debug!(target: "gear::gl", "debug output is not available");
+        }
+
+        let group = share.map_or_else(Rc::default, |share| share.0.group.clone());
+        group.windows.borrow_mut().push(window);
+
+        GlContext(Rc::new(ContextInner { window: Cell::new(window), group, functions, windowless: false }))
+    }
+
+    /// A context that isn't tied to a window, e.g. one created by another windowing library or
+    /// `mock::context`. Calls are never checked against the current context; that's up to the caller.
+    pub fn load_with<F: FnMut(&'static str) -> *const c_void>(loader: F) -> Self {
+        GlContext(Rc::new(ContextInner {
+            window: Cell::new(std::ptr::null_mut()),
+            group: Rc::default(),
+            functions: GLFunctions::load_with(loader),
+            windowless: true,
+        }))
+    }
+
+    /// Fails with `NoContext` unless this context, or one sharing objects with it, is current on
+    /// this thread. Use this for objects GL shares between contexts (buffers, textures, programs).
+    pub fn gl(&self) -> Result<&GLFunctions> {
+        if self.0.windowless {
+            return Ok(&self.0.functions);
+        }
+
+        let current = unsafe { glfwGetCurrentContext() };
+        if !current.is_null() && self.0.group.contains(current) {
+            Ok(&self.0.functions)
+        } else {
+            Err(GearError::NoContext)
+        }
+    }
+
+    /// Container objects (vertex arrays, framebuffers) are never shared, so they need their own
+    /// context to be current.
+    pub fn gl_unshared(&self) -> Result<&GLFunctions> {
+        if self.is_current() {
+            Ok(&self.0.functions)
+        } else {
+            Err(GearError::NoContext)
+        }
+    }
+
+    pub fn window(&self) -> *mut GLFWwindow {
+        self.0.window.get()
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.0.windowless || !self.window().is_null()
+    }
+
+    pub fn is_current(&self) -> bool {
+        self.0.windowless || (self.is_alive() && unsafe { glfwGetCurrentContext() } == self.window())
+    }
+
+    pub fn shares_with(&self, other: &GlContext) -> bool {
+        Rc::ptr_eq(&self.0.group, &other.0.group)
+    }
+
+    pub fn make_current(&self) -> Result<()> {
+        if !self.is_alive() {
+            return Err(GearError::NoContext);
+        }
+
+        if !self.0.windowless {
+            unsafe { glfwMakeContextCurrent(self.window()) };
+        }
+        Ok(())
+    }
+
+    // Size of the window's default framebuffer. Windowless contexts don't know theirs.
+    pub(crate) fn framebuffer_size(&self) -> Result<(c_int, c_int)> {
+        if self.window().is_null() {
+            return Err(GearError::InvalidArgument("a windowless context has no default framebuffer size".into()));
+        }
+
+        Ok(crate::window::framebuffer_size(self.window()))
+    }
+
+    /// Clears the buffers in `mask` of whatever framebuffer is bound, the color ones to `color`.
+    pub fn clear(&self, mask: BufferMask, color: [f32; 4]) -> Result<()> {
+        let gl = self.gl_unshared()?;
+        unsafe {
+            if mask.contains(BufferMask::COLOR) {
+                gl.ClearColor(color[0], color[1], color[2], color[3])?;
+            }
+            gl.Clear(mask.raw())
+        }
+    }
+
+    /// Sets the area of the bound framebuffer that is drawn to, in pixels from the bottom left.
+    pub fn set_viewport(&self, x: c_int, y: c_int, width: c_int, height: c_int) -> Result<()> {
+        if width < 0 || height < 0 {
+            return Err(GearError::InvalidArgument("viewport size can't be negative".into()));
+        }
+
+        unsafe { self.gl_unshared()?.Viewport(x, y, width, height) }
+    }
+
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.0.functions.is_loaded(name)
+    }
+
+    // Runs `f` with a context of the group current, switching to one (and back afterwards) if
+    // needed. Used when deleting objects, which may happen while another window is being drawn.
+    pub(crate) fn with_current<R>(&self, unshared: bool, f: impl FnOnce(&GLFunctions) -> R) -> Option<R> {
+        let gl = if unshared { self.gl_unshared() } else { self.gl() };
+        if let Ok(gl) = gl {
+            return Some(f(gl));
+        }
+
+        let target = if unshared || self.is_alive() { Some(self.window()) } else { self.0.group.first() };
+        let target = target.filter(|window| !window.is_null())?;
+
+        unsafe {
+            let previous = glfwGetCurrentContext();
+            glfwMakeContextCurrent(target);
+            let result = f(&self.0.functions);
+            glfwMakeContextCurrent(previous);
+            Some(result)
+        }
+    }
+
+    // Called when the window goes away; shared objects stay usable from the rest of the group.
+    pub(crate) fn invalidate(&self) {
+        let window = self.0.window.replace(std::ptr::null_mut());
+        self.0.group.windows.borrow_mut().retain(|member| *member != window);
+    }
+}
+
+// GLX hands out a pointer for any gl* name, so a loaded glDebugMessageCallback doesn't mean the
+// driver supports it; the context version and extension string do. `window` has to be current.
+#[cfg(feature = "gl-debug")]
+fn has_debug_output(window: *mut GLFWwindow) -> bool {
+    unsafe {
+        let version = (glfwGetWindowAttrib(window, GLFWContextVersionMajor), glfwGetWindowAttrib(window, GLFWContextVersionMinor));
+        version >= (4, 3) || glfwExtensionSupported(c"GL_KHR_debug".as_ptr()) != False
+    }
+}
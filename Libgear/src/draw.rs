@@ -0,0 +1,124 @@
+use std::{ffi::c_void, ops::{Bound, RangeBounds}};
+
+use crate::{error::*, gl, gl::types::*, shader::ShaderProgram, vertex::{index_size, VertexArray}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Primitive {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+}
+
+impl Primitive {
+    pub fn gl(self) -> GLenum {
+        match self {
+            Primitive::Points => gl::POINTS,
+            Primitive::Lines => gl::LINES,
+            Primitive::LineStrip => gl::LINE_STRIP,
+            Primitive::LineLoop => gl::LINE_LOOP,
+            Primitive::Triangles => gl::TRIANGLES,
+            Primitive::TriangleStrip => gl::TRIANGLE_STRIP,
+            Primitive::TriangleFan => gl::TRIANGLE_FAN,
+        }
+    }
+}
+
+/// Draws the vertices in `range` of the vertex array, or all of them with `..`.
+pub fn draw_arrays<R: RangeBounds<usize>>(
+    program: &ShaderProgram,
+    vao: &VertexArray,
+    primitive: Primitive,
+    range: R,
+) -> Result<()> {
+    draw_arrays_instanced(program, vao, primitive, range, 1)
+}
+
+/// Draws the vertices in `range` `instances` times.
+pub fn draw_arrays_instanced<R: RangeBounds<usize>>(
+    program: &ShaderProgram,
+    vao: &VertexArray,
+    primitive: Primitive,
+    range: R,
+    instances: usize,
+) -> Result<()> {
+    let (first, count) = resolve(range, vao.vertex_count(), "vertex")?;
+
+    program.use_program()?;
+    vao.bind()?;
+
+    let result = unsafe {
+        if instances == 1 {
+            vao.context().gl_unshared()?.DrawArrays(primitive.gl(), first as GLint, count as GLsizei)
+        } else {
+            vao.context().gl_unshared()?.DrawArraysInstanced(primitive.gl(), first as GLint, count as GLsizei, instances as GLsizei)
+        }
+    };
+
+    // Left bound, the next element array buffer uploaded anywhere would replace its indices.
+    vao.unbind()?;
+    result
+}
+
+/// Draws the indices in `range` of the vertex array's index buffer, or all of them with `..`.
+pub fn draw_elements<R: RangeBounds<usize>>(
+    program: &ShaderProgram,
+    vao: &VertexArray,
+    primitive: Primitive,
+    range: R,
+) -> Result<()> {
+    draw_elements_instanced(program, vao, primitive, range, 1)
+}
+
+/// Draws the indices in `range` `instances` times.
+pub fn draw_elements_instanced<R: RangeBounds<usize>>(
+    program: &ShaderProgram,
+    vao: &VertexArray,
+    primitive: Primitive,
+    range: R,
+    instances: usize,
+) -> Result<()> {
+    let index_type = vao.index_type()
+        .ok_or_else(|| GearError::InvalidArgument("vertex array has no index buffer".into()))?;
+    let (first, count) = resolve(range, vao.index_count(), "index")?;
+    let offset = (first * index_size(index_type)) as *const c_void;
+
+    program.use_program()?;
+    vao.bind()?;
+
+    let result = unsafe {
+        if instances == 1 {
+            vao.context().gl_unshared()?.DrawElements(primitive.gl(), count as GLsizei, index_type, offset)
+        } else {
+            vao.context().gl_unshared()?.DrawElementsInstanced(primitive.gl(), count as GLsizei, index_type, offset, instances as GLsizei)
+        }
+    };
+
+    vao.unbind()?;
+    result
+}
+
+fn resolve<R: RangeBounds<usize>>(range: R, len: usize, what: &str) -> Result<(usize, usize)> {
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => end + 1,
+        Bound::Excluded(end) => *end,
+        Bound::Unbounded => len,
+    };
+
+    if start > end || end > len {
+        return Err(GearError::InvalidArgument(format!(
+            "{} range {}..{} is out of bounds for {} elements",
+            what, start, end, len
+        )));
+    }
+
+    Ok((start, end - start))
+}
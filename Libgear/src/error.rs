@@ -0,0 +1,123 @@
+use std::{cell::RefCell, ffi::*, fmt, ptr::null};
+
+use crate::{gl, gl::types::GLenum, shader::ShaderStage};
+
+pub const GEAR_OK: c_int = 0;
+pub const GEAR_ERROR_INIT: c_int = 1;
+pub const GEAR_ERROR_WINDOW_CREATION: c_int = 2;
+pub const GEAR_ERROR_INVALID_ARGUMENT: c_int = 3;
+pub const GEAR_ERROR_MISSING_SYMBOL: c_int = 4;
+pub const GEAR_ERROR_SHADER_COMPILE: c_int = 5;
+pub const GEAR_ERROR_NO_CONTEXT: c_int = 6;
+pub const GEAR_ERROR_IMAGE: c_int = 7;
+pub const GEAR_ERROR_FRAMEBUFFER_INCOMPLETE: c_int = 8;
+pub const GEAR_ERROR_GL: c_int = 9;
+
+/// Result of everything in this crate that can fail.
+pub type Result<T> = std::result::Result<T, GearError>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GearError {
+    Init,
+    WindowCreation,
+    InvalidArgument(String),
+    MissingSymbol(String),
+    ShaderCompile { stage: ShaderStage, log: String },
+    NoContext,
+    Image(String),
+    FramebufferIncomplete(GLenum),
+    Gl { function: String, code: GLenum },
+}
+
+impl GearError {
+    pub fn code(&self) -> c_int {
+        match self {
+            GearError::Init => GEAR_ERROR_INIT,
+            GearError::WindowCreation => GEAR_ERROR_WINDOW_CREATION,
+            GearError::InvalidArgument(_) => GEAR_ERROR_INVALID_ARGUMENT,
+            GearError::MissingSymbol(_) => GEAR_ERROR_MISSING_SYMBOL,
+            GearError::ShaderCompile { .. } => GEAR_ERROR_SHADER_COMPILE,
+            GearError::NoContext => GEAR_ERROR_NO_CONTEXT,
+            GearError::Image(_) => GEAR_ERROR_IMAGE,
+            GearError::FramebufferIncomplete(_) => GEAR_ERROR_FRAMEBUFFER_INCOMPLETE,
+            GearError::Gl { .. } => GEAR_ERROR_GL,
+        }
+    }
+}
+
+impl fmt::Display for GearError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GearError::Init => write!(f, "failed to initialize GLFW"),
+            GearError::WindowCreation => write!(f, "failed to create window"),
+            GearError::InvalidArgument(message) => write!(f, "invalid argument: {}", message),
+            GearError::MissingSymbol(name) => write!(f, "OpenGL function {} is not available", name),
+            GearError::ShaderCompile { stage: ShaderStage::Link, log } => write!(f, "failed to link shader program: {}", log),
+            GearError::ShaderCompile { stage, log } => write!(f, "failed to compile {} shader: {}", stage, log),
+            GearError::NoContext => write!(f, "no OpenGL context is current"),
+            GearError::Image(message) => write!(f, "image error: {}", message),
+            GearError::FramebufferIncomplete(status) => write!(f, "framebuffer is incomplete: {}", framebuffer_status(*status)),
+            GearError::Gl { function, code } => write!(f, "{} raised {}", function, gl_error_name(*code)),
+        }
+    }
+}
+
+impl std::error::Error for GearError {}
+
+pub(crate) fn gl_error_name(code: GLenum) -> String {
+    match code {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM".into(),
+        gl::INVALID_VALUE => "GL_INVALID_VALUE".into(),
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION".into(),
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION".into(),
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY".into(),
+        code => format!("GL error 0x{:X}", code),
+    }
+}
+
+fn framebuffer_status(status: GLenum) -> String {
+    match status {
+        gl::FRAMEBUFFER_UNDEFINED => "no default framebuffer".into(),
+        gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => "an attachment is incomplete".into(),
+        gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => "nothing is attached".into(),
+        gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => "a draw buffer has no attachment".into(),
+        gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => "the read buffer has no attachment".into(),
+        gl::FRAMEBUFFER_UNSUPPORTED => "the attachment formats are not supported together".into(),
+        gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => "attachments have different sample counts".into(),
+        gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => "attachments have different layer targets".into(),
+        status => format!("status 0x{:X}", status),
+    }
+}
+
+impl From<image::ImageError> for GearError {
+    fn from(error: image::ImageError) -> Self {
+        GearError::Image(error.to_string())
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn set_last_error(error: &GearError) {
+    let message = CString::new(error.to_string().replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+// Converts a result into the status code handed back over the C ABI.
+pub(crate) fn status(result: Result<()>) -> c_int {
+    match result {
+        Ok(()) => GEAR_OK,
+        Err(error) => {
+            set_last_error(&error);
+            error.code()
+        }
+    }
+}
+
+pub(crate) fn last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| match last.borrow().as_ref() {
+        Some(message) => message.as_ptr(),
+        None => null(),
+    })
+}
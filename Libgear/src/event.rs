@@ -0,0 +1,20 @@
+use std::{ffi::c_int, path::PathBuf};
+
+use crate::input::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Resized { width: c_int, height: c_int },
+    FramebufferResized { width: c_int, height: c_int },
+    Key { key: Key, scancode: c_int, action: Action, mods: Modifiers },
+    Char(char),
+    CursorMoved { x: f64, y: f64 },
+    CursorEntered(bool),
+    MouseButton { button: MouseButton, action: Action, mods: Modifiers },
+    Scroll { x: f64, y: f64 },
+    Focused(bool),
+    Iconified(bool),
+    CloseRequested,
+    FileDrop(Vec<PathBuf>),
+    ContentScale { x: f32, y: f32 },
+}
@@ -0,0 +1,371 @@
+use std::ops::BitOr;
+
+use crate::{context::GlContext, error::*, gl::{self, types::*, GLFunctions}, image::*, texture::*};
+
+// COLOR_ATTACHMENT31 is the last one GL defines; drivers usually support 8.
+const MAX_COLOR_ATTACHMENTS: u32 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attachment {
+    Color(u32),
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+impl Attachment {
+    pub fn gl(self) -> GLenum {
+        match self {
+            Attachment::Color(index) => gl::COLOR_ATTACHMENT0 + index,
+            Attachment::Depth => gl::DEPTH_ATTACHMENT,
+            Attachment::Stencil => gl::STENCIL_ATTACHMENT,
+            Attachment::DepthStencil => gl::DEPTH_STENCIL_ATTACHMENT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BufferMask(GLbitfield);
+
+impl BufferMask {
+    pub const COLOR: BufferMask = BufferMask(gl::COLOR_BUFFER_BIT);
+    pub const DEPTH: BufferMask = BufferMask(gl::DEPTH_BUFFER_BIT);
+    pub const STENCIL: BufferMask = BufferMask(gl::STENCIL_BUFFER_BIT);
+
+    pub fn raw(self) -> GLbitfield {
+        self.0
+    }
+
+    pub fn contains(self, other: BufferMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for BufferMask {
+    type Output = BufferMask;
+
+    fn bitor(self, rhs: BufferMask) -> BufferMask {
+        BufferMask(self.0 | rhs.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderbufferFormat {
+    Rgba8,
+    Rgba16F,
+    Depth24,
+    Depth32F,
+    Depth24Stencil8,
+    Stencil8,
+}
+
+impl RenderbufferFormat {
+    pub fn gl(self) -> GLenum {
+        match self {
+            RenderbufferFormat::Rgba8 => gl::RGBA8,
+            RenderbufferFormat::Rgba16F => gl::RGBA16F,
+            RenderbufferFormat::Depth24 => gl::DEPTH_COMPONENT24,
+            RenderbufferFormat::Depth32F => gl::DEPTH_COMPONENT32F,
+            RenderbufferFormat::Depth24Stencil8 => gl::DEPTH24_STENCIL8,
+            RenderbufferFormat::Stencil8 => gl::STENCIL_INDEX8,
+        }
+    }
+
+    fn fits(self, attachment: Attachment) -> bool {
+        match attachment {
+            Attachment::Color(_) => matches!(self, RenderbufferFormat::Rgba8 | RenderbufferFormat::Rgba16F),
+            Attachment::Depth => matches!(
+                self,
+                RenderbufferFormat::Depth24 | RenderbufferFormat::Depth32F | RenderbufferFormat::Depth24Stencil8
+            ),
+            Attachment::Stencil => matches!(self, RenderbufferFormat::Stencil8 | RenderbufferFormat::Depth24Stencil8),
+            Attachment::DepthStencil => self == RenderbufferFormat::Depth24Stencil8,
+        }
+    }
+}
+
+/// Storage that can only be rendered to, not sampled. Multisampled color targets live here.
+pub struct Renderbuffer {
+    context: GlContext,
+    name: GLuint,
+    width: GLsizei,
+    height: GLsizei,
+    format: RenderbufferFormat,
+    samples: GLsizei,
+}
+
+impl Renderbuffer {
+    pub fn new(context: &GlContext, width: GLsizei, height: GLsizei, format: RenderbufferFormat) -> Result<Self> {
+        Renderbuffer::multisampled(context, width, height, format, 0)
+    }
+
+    pub fn multisampled(
+        context: &GlContext,
+        width: GLsizei,
+        height: GLsizei,
+        format: RenderbufferFormat,
+        samples: GLsizei,
+    ) -> Result<Self> {
+        if width <= 0 || height <= 0 {
+            return Err(GearError::InvalidArgument("renderbuffer size must be greater than 0".into()));
+        }
+
+        let gl = context.gl()?;
+        let mut name: GLuint = 0;
+        unsafe { gl.GenRenderbuffers(1, &mut name)? };
+
+        let renderbuffer = Renderbuffer {
+            context: context.clone(),
+            name,
+            width,
+            height,
+            format,
+            samples,
+        };
+
+        unsafe {
+            gl.BindRenderbuffer(gl::RENDERBUFFER, name)?;
+            if samples > 0 {
+                gl.RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, format.gl(), width, height)?;
+            } else {
+                gl.RenderbufferStorage(gl::RENDERBUFFER, format.gl(), width, height)?;
+            }
+            gl.BindRenderbuffer(gl::RENDERBUFFER, 0)?;
+        }
+
+        Ok(renderbuffer)
+    }
+
+    pub fn name(&self) -> GLuint {
+        self.name
+    }
+
+    pub fn size(&self) -> (GLsizei, GLsizei) {
+        (self.width, self.height)
+    }
+
+    pub fn format(&self) -> RenderbufferFormat {
+        self.format
+    }
+
+    pub fn samples(&self) -> GLsizei {
+        self.samples
+    }
+}
+
+impl Drop for Renderbuffer {
+    fn drop(&mut self) {
+        let _ = self.context.with_current(false, |gl| unsafe { gl.DeleteRenderbuffers(1, &self.name) });
+    }
+}
+
+/// Attachments are not owned by the framebuffer; keep them alive for as long as it is drawn to.
+pub struct Framebuffer {
+    context: GlContext,
+    name: GLuint,
+    width: GLsizei,
+    height: GLsizei,
+    draw_buffers: Vec<GLenum>,
+}
+
+impl Framebuffer {
+    pub fn new(context: &GlContext, width: GLsizei, height: GLsizei) -> Result<Self> {
+        if width <= 0 || height <= 0 {
+            return Err(GearError::InvalidArgument("framebuffer size must be greater than 0".into()));
+        }
+
+        let mut name: GLuint = 0;
+        unsafe { context.gl_unshared()?.GenFramebuffers(1, &mut name)? };
+
+        Ok(Framebuffer {
+            context: context.clone(),
+            name,
+            width,
+            height,
+            draw_buffers: Vec::new(),
+        })
+    }
+
+    pub fn context(&self) -> &GlContext {
+        &self.context
+    }
+
+    pub fn name(&self) -> GLuint {
+        self.name
+    }
+
+    pub fn size(&self) -> (GLsizei, GLsizei) {
+        (self.width, self.height)
+    }
+
+    pub fn attach_texture(&mut self, attachment: Attachment, texture: &Texture2D) -> Result<()> {
+        self.check_attachment_size(texture.size())?;
+        if texture.format().is_depth() == matches!(attachment, Attachment::Color(_)) {
+            return Err(GearError::InvalidArgument(format!(
+                "{:?} texture can't be used as a {:?} attachment",
+                texture.format(), attachment
+            )));
+        }
+
+        self.attach(attachment, |gl| unsafe {
+            gl.FramebufferTexture2D(gl::FRAMEBUFFER, attachment.gl(), gl::TEXTURE_2D, texture.name(), 0)
+        })
+    }
+
+    pub fn attach_renderbuffer(&mut self, attachment: Attachment, renderbuffer: &Renderbuffer) -> Result<()> {
+        self.check_attachment_size(renderbuffer.size())?;
+        if !renderbuffer.format().fits(attachment) {
+            return Err(GearError::InvalidArgument(format!(
+                "{:?} renderbuffer can't be used as a {:?} attachment",
+                renderbuffer.format(), attachment
+            )));
+        }
+
+        self.attach(attachment, |gl| unsafe {
+            gl.FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment.gl(), gl::RENDERBUFFER, renderbuffer.name())
+        })
+    }
+
+    pub fn check(&self) -> Result<()> {
+        let gl = self.context.gl_unshared()?;
+        let status = unsafe {
+            restoring_bindings(gl, || {
+                gl.BindFramebuffer(gl::FRAMEBUFFER, self.name)?;
+                gl.CheckFramebufferStatus(gl::FRAMEBUFFER)
+            })?
+        };
+
+        if status == gl::FRAMEBUFFER_COMPLETE {
+            Ok(())
+        } else {
+            Err(GearError::FramebufferIncomplete(status))
+        }
+    }
+
+    /// Binds for drawing and sets the viewport to cover the whole framebuffer.
+    pub fn bind(&self) -> Result<()> {
+        let gl = self.context.gl_unshared()?;
+        unsafe {
+            gl.BindFramebuffer(gl::FRAMEBUFFER, self.name)?;
+            gl.Viewport(0, 0, self.width, self.height)
+        }
+    }
+
+    /// Goes back to the default framebuffer and, for a window, its viewport. Windowless contexts
+    /// don't know the size of theirs, so the viewport is left for the caller to set.
+    pub fn unbind(&self) -> Result<()> {
+        let gl = self.context.gl_unshared()?;
+        unsafe { gl.BindFramebuffer(gl::FRAMEBUFFER, 0)? };
+        if self.context.window().is_null() {
+            return Ok(());
+        }
+
+        let (width, height) = self.context.framebuffer_size()?;
+        unsafe { gl.Viewport(0, 0, width, height) }
+    }
+
+    /// Copies into `target`, or into the window when `None`, scaling to its size. Resolving a
+    /// multisampled framebuffer is a blit into a single-sampled one of the same size.
+    pub fn blit(&self, target: Option<&Framebuffer>, mask: BufferMask, filter: MagFilter) -> Result<()> {
+        if filter == MagFilter::Linear && (mask.contains(BufferMask::DEPTH) || mask.contains(BufferMask::STENCIL)) {
+            return Err(GearError::InvalidArgument("depth and stencil can only be blitted with nearest filtering".into()));
+        }
+
+        let gl = self.context.gl_unshared()?;
+        let (target_name, (width, height)) = match target {
+            Some(target) => (target.name, target.size()),
+            None => (0, self.context.framebuffer_size()?),
+        };
+
+        unsafe {
+            restoring_bindings(gl, || {
+                gl.BindFramebuffer(gl::READ_FRAMEBUFFER, self.name)?;
+                gl.BindFramebuffer(gl::DRAW_FRAMEBUFFER, target_name)?;
+                gl.BlitFramebuffer(0, 0, self.width, self.height, 0, 0, width, height, mask.raw(), filter.gl())
+            })
+        }
+    }
+
+    /// Multisampled framebuffers have to be resolved into a single-sampled one first.
+    pub fn read_pixels(&self, attachment: u32) -> Result<Image> {
+        let gl = self.context.gl_unshared()?;
+        unsafe {
+            restoring_bindings(gl, || {
+                gl.BindFramebuffer(gl::READ_FRAMEBUFFER, self.name)?;
+                gl.ReadBuffer(Attachment::Color(attachment).gl())?;
+                read_pixels(gl, self.width, self.height)
+            })
+        }
+    }
+
+    pub fn resolve(&self, target: &Framebuffer) -> Result<()> {
+        if target.size() != self.size() {
+            return Err(GearError::InvalidArgument("multisample resolve needs framebuffers of the same size".into()));
+        }
+
+        self.blit(Some(target), BufferMask::COLOR, MagFilter::Nearest)
+    }
+
+    fn check_attachment_size(&self, size: (GLsizei, GLsizei)) -> Result<()> {
+        if size != self.size() {
+            return Err(GearError::InvalidArgument(format!(
+                "{}x{} attachment doesn't match the {}x{} framebuffer",
+                size.0, size.1, self.width, self.height
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Attaches with the framebuffer bound and puts back whatever was bound before, so setting one
+    // up never redirects what is drawn next. Fragment output `n` is written to `Color(n)`; the draw
+    // buffers of unused locations are `NONE`.
+    fn attach(&mut self, attachment: Attachment, f: impl FnOnce(&GLFunctions) -> Result<()>) -> Result<()> {
+        let mut draw_buffers = self.draw_buffers.clone();
+        if let Attachment::Color(index) = attachment {
+            if index >= MAX_COLOR_ATTACHMENTS {
+                return Err(GearError::InvalidArgument(format!("color attachment {} is out of range", index)));
+            }
+
+            let index = index as usize;
+            if draw_buffers.len() <= index {
+                draw_buffers.resize(index + 1, gl::NONE);
+            }
+            draw_buffers[index] = attachment.gl();
+        }
+
+        let gl = self.context.gl_unshared()?;
+        unsafe {
+            restoring_bindings(gl, || {
+                gl.BindFramebuffer(gl::FRAMEBUFFER, self.name)?;
+                f(gl)?;
+                if draw_buffers == self.draw_buffers {
+                    return Ok(());
+                }
+                gl.DrawBuffers(draw_buffers.len() as GLsizei, draw_buffers.as_ptr())
+            })?;
+        }
+
+        self.draw_buffers = draw_buffers;
+        Ok(())
+    }
+}
+
+// Runs `f` and binds the draw and read framebuffers that were bound before it again, even when it
+// fails halfway.
+unsafe fn restoring_bindings<T>(gl: &GLFunctions, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let (mut draw, mut read): (GLint, GLint) = (0, 0);
+    gl.GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut draw)?;
+    gl.GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut read)?;
+
+    let result = f();
+    gl.BindFramebuffer(gl::DRAW_FRAMEBUFFER, draw as GLuint)?;
+    gl.BindFramebuffer(gl::READ_FRAMEBUFFER, read as GLuint)?;
+    result
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        let _ = self.context.with_current(true, |gl| unsafe { gl.DeleteFramebuffers(1, &self.name) });
+    }
+}
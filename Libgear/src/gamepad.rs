@@ -0,0 +1,373 @@
+use std::{ffi::*, slice, sync::{Mutex, MutexGuard, PoisonError}};
+
+use crate::{error::*, types::*};
+use super::*;
+
+type JoystickCallback = Box<dyn FnMut(Joystick, JoystickEvent) + Send>;
+
+// The generation changes whenever the callback is set or cleared, so one that replaces or
+// clears itself while running isn't put back afterwards.
+static JOYSTICK_CALLBACK: Mutex<(u64, Option<JoystickCallback>)> = Mutex::new((0, None));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JoystickEvent {
+    Connected,
+    Disconnected,
+}
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    A = 0,
+    B = 1,
+    X = 2,
+    Y = 3,
+    LeftBumper = 4,
+    RightBumper = 5,
+    Back = 6,
+    Start = 7,
+    Guide = 8,
+    LeftThumb = 9,
+    RightThumb = 10,
+    DpadUp = 11,
+    DpadRight = 12,
+    DpadDown = 13,
+    DpadLeft = 14,
+}
+
+impl GamepadButton {
+    pub const CROSS: GamepadButton = GamepadButton::A;
+    pub const CIRCLE: GamepadButton = GamepadButton::B;
+    pub const SQUARE: GamepadButton = GamepadButton::X;
+    pub const TRIANGLE: GamepadButton = GamepadButton::Y;
+}
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftX = 0,
+    LeftY = 1,
+    RightX = 2,
+    RightY = 3,
+    LeftTrigger = 4,
+    RightTrigger = 5,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadStick {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeadZone {
+    pub inner: f32,
+    pub outer: f32,
+}
+
+impl Default for DeadZone {
+    fn default() -> Self {
+        DeadZone { inner: 0.15, outer: 1.0 }
+    }
+}
+
+impl DeadZone {
+    pub fn new(inner: f32, outer: f32) -> Self {
+        DeadZone { inner, outer }
+    }
+
+    /// Rescales one axis so the usable range still starts at 0 just outside the dead zone.
+    pub fn apply_axis(&self, value: f32) -> f32 {
+        value.signum() * self.rescale(value.abs())
+    }
+
+    /// Radial dead zone, so diagonals don't snap to the axes the way two per-axis zones would.
+    pub fn apply_stick(&self, x: f32, y: f32) -> (f32, f32) {
+        let magnitude = (x * x + y * y).sqrt();
+        if magnitude <= self.inner {
+            return (0.0, 0.0);
+        }
+
+        let scale = self.rescale(magnitude) / magnitude;
+        (x * scale, y * scale)
+    }
+
+    fn rescale(&self, magnitude: f32) -> f32 {
+        if magnitude <= self.inner {
+            0.0
+        } else if magnitude >= self.outer || self.outer <= self.inner {
+            1.0
+        } else {
+            (magnitude - self.inner) / (self.outer - self.inner)
+        }
+    }
+}
+
+/// Same layout as GLFWgamepadstate.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GamepadState {
+    pub buttons: [c_uchar; 15],
+    pub axes: [c_float; 6],
+}
+
+impl GamepadState {
+    pub fn from_raw(buttons: [c_uchar; 15], axes: [c_float; 6]) -> Self {
+        GamepadState { buttons, axes }
+    }
+
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons[button as usize] as c_int == GLFWPress
+    }
+
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis as usize]
+    }
+
+    pub fn stick(&self, stick: GamepadStick, dead_zone: &DeadZone) -> (f32, f32) {
+        let (x, y) = match stick {
+            GamepadStick::Left => (GamepadAxis::LeftX, GamepadAxis::LeftY),
+            GamepadStick::Right => (GamepadAxis::RightX, GamepadAxis::RightY),
+        };
+
+        dead_zone.apply_stick(self.axis(x), self.axis(y))
+    }
+
+    /// Triggers rest at -1 in the standard mapping; this returns them in [0, 1].
+    pub fn trigger(&self, axis: GamepadAxis, dead_zone: &DeadZone) -> f32 {
+        dead_zone.apply_axis((self.axis(axis) + 1.0) / 2.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Joystick(c_int);
+
+/// GLFW has to be initialized (a window has to exist) before joysticks can be queried.
+impl Joystick {
+    pub const LAST: c_int = 15;
+
+    pub fn new(id: c_int) -> Option<Joystick> {
+        (0..=Joystick::LAST).contains(&id).then_some(Joystick(id))
+    }
+
+    pub fn all() -> impl Iterator<Item = Joystick> {
+        (0..=Joystick::LAST).map(Joystick)
+    }
+
+    pub fn connected() -> impl Iterator<Item = Joystick> {
+        Joystick::all().filter(Joystick::is_present)
+    }
+
+    pub fn id(&self) -> c_int {
+        self.0
+    }
+
+    pub fn is_present(&self) -> bool {
+        unsafe { glfwJoystickPresent(self.0) != False }
+    }
+
+    pub fn is_gamepad(&self) -> bool {
+        unsafe { glfwJoystickIsGamepad(self.0) != False }
+    }
+
+    pub fn name(&self) -> Option<String> {
+        unsafe { string(glfwGetJoystickName(self.0)) }
+    }
+
+    pub fn gamepad_name(&self) -> Option<String> {
+        unsafe { string(glfwGetGamepadName(self.0)) }
+    }
+
+    pub fn guid(&self) -> Option<String> {
+        unsafe { string(glfwGetJoystickGUID(self.0)) }
+    }
+
+    pub fn axes(&self) -> Vec<f32> {
+        let mut count = 0;
+        let axes = unsafe { glfwGetJoystickAxes(self.0, &mut count) };
+        if axes.is_null() {
+            return Vec::new();
+        }
+
+        unsafe { slice::from_raw_parts(axes, count.max(0) as usize) }.to_vec()
+    }
+
+    pub fn buttons(&self) -> Vec<bool> {
+        let mut count = 0;
+        let buttons = unsafe { glfwGetJoystickButtons(self.0, &mut count) };
+        if buttons.is_null() {
+            return Vec::new();
+        }
+
+        unsafe { slice::from_raw_parts(buttons, count.max(0) as usize) }
+            .iter()
+            .map(|button| *button as c_int == GLFWPress)
+            .collect()
+    }
+
+    pub fn gamepad_state(&self) -> Option<GamepadState> {
+        let mut state = GamepadState::default();
+        unsafe { (glfwGetGamepadState(self.0, &mut state) != False).then_some(state) }
+    }
+}
+
+/// Can be called from inside the callback to replace it.
+pub fn set_joystick_callback<F: FnMut(Joystick, JoystickEvent) + Send + 'static>(callback: F) {
+    replace_callback(Some(Box::new(callback)));
+    unsafe { glfwSetJoystickCallback(Some(joystick_callback)) };
+}
+
+/// Stops reporting joystick connections.
+pub fn clear_joystick_callback() {
+    replace_callback(None);
+    unsafe { glfwSetJoystickCallback(None) };
+}
+
+fn replace_callback(callback: Option<JoystickCallback>) {
+    let mut slot = lock_callback();
+    slot.0 += 1;
+    slot.1 = callback;
+}
+
+// A callback that panicked poisons the lock but leaves the slot in a usable state.
+fn lock_callback() -> MutexGuard<'static, (u64, Option<JoystickCallback>)> {
+    JOYSTICK_CALLBACK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Takes mappings in the SDL_GameControllerDB format, one controller per line.
+pub fn update_gamepad_mappings(mappings: &str) -> Result<()> {
+    let mappings = CString::new(mappings)
+        .map_err(|_| GearError::InvalidArgument("gamepad mappings contain a nul byte".into()))?;
+
+    if unsafe { glfwUpdateGamepadMappings(mappings.as_ptr()) } == False {
+        return Err(GearError::InvalidArgument("gamepad mappings could not be parsed".into()));
+    }
+
+    Ok(())
+}
+
+extern "C" fn joystick_callback(id: c_int, event: c_int) {
+    let event = if event == GLFWConnected {
+        JoystickEvent::Connected
+    } else if event == GLFWDisconnected {
+        JoystickEvent::Disconnected
+    } else {
+        return;
+    };
+
+    // The lock isn't held while the callback runs, so it can set or clear the callback itself.
+    let (generation, callback) = {
+        let mut slot = lock_callback();
+        (slot.0, slot.1.take())
+    };
+    let Some(mut callback) = callback else { return };
+
+    callback(Joystick(id), event);
+
+    let mut slot = lock_callback();
+    if slot.0 == generation {
+        slot.1 = Some(callback);
+    }
+}
+
+unsafe fn string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+
+    use super::*;
+
+    const RELEASED: c_uchar = 0;
+    const PRESSED: c_uchar = 1;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    // A controller held with the left stick pushed diagonally, the right stick resting inside the
+    // dead zone, the left trigger half pulled and A and the d-pad left pressed.
+    fn recorded_state() -> GamepadState {
+        let mut buttons = [RELEASED; 15];
+        buttons[GamepadButton::A as usize] = PRESSED;
+        buttons[GamepadButton::DpadLeft as usize] = PRESSED;
+        GamepadState::from_raw(buttons, [0.6, -0.6, 0.05, -0.08, 0.0, -1.0])
+    }
+
+    #[test]
+    fn buttons() {
+        let state = recorded_state();
+        assert!(state.is_pressed(GamepadButton::A));
+        assert!(state.is_pressed(GamepadButton::CROSS));
+        assert!(state.is_pressed(GamepadButton::DpadLeft));
+        assert!(!state.is_pressed(GamepadButton::B));
+        assert!(!state.is_pressed(GamepadButton::Start));
+    }
+
+    #[test]
+    fn axis_dead_zone() {
+        let dead_zone = DeadZone::new(0.2, 0.9);
+        assert_eq!(dead_zone.apply_axis(0.1), 0.0);
+        assert_eq!(dead_zone.apply_axis(-0.2), 0.0);
+        assert!(close(dead_zone.apply_axis(0.55), 0.5));
+        assert!(close(dead_zone.apply_axis(-0.55), -0.5));
+        assert_eq!(dead_zone.apply_axis(0.95), 1.0);
+        assert_eq!(dead_zone.apply_axis(-1.0), -1.0);
+    }
+
+    #[test]
+    fn radial_dead_zone() {
+        let state = recorded_state();
+        let dead_zone = DeadZone::default();
+
+        assert_eq!(state.stick(GamepadStick::Right, &dead_zone), (0.0, 0.0));
+
+        // Direction is kept and only the magnitude is rescaled.
+        let (x, y) = state.stick(GamepadStick::Left, &dead_zone);
+        let magnitude = (0.72f32).sqrt();
+        let expected = (magnitude - 0.15) / 0.85 / magnitude;
+        assert!(close(x, 0.6 * expected) && close(y, -0.6 * expected), "{} {}", x, y);
+
+        // Each axis alone would be inside a 0.7 zone, but together they are outside it.
+        let (x, y) = DeadZone::new(0.7, 1.0).apply_stick(0.6, 0.6);
+        assert!(x > 0.0 && close(x, y));
+    }
+
+    #[test]
+    fn triggers_are_remapped_to_zero_one() {
+        let state = recorded_state();
+        let dead_zone = DeadZone::new(0.0, 1.0);
+        assert!(close(state.trigger(GamepadAxis::LeftTrigger, &dead_zone), 0.5));
+        assert_eq!(state.trigger(GamepadAxis::RightTrigger, &dead_zone), 0.0);
+        assert_eq!(state.trigger(GamepadAxis::RightTrigger, &DeadZone::default()), 0.0);
+    }
+
+    #[test]
+    fn callback_can_replace_itself() {
+        let first = Arc::new(AtomicUsize::new(0));
+        let second = Arc::new(AtomicUsize::new(0));
+
+        let (first_count, second_count) = (first.clone(), second.clone());
+        set_joystick_callback(move |_, _| {
+            first_count.fetch_add(1, Ordering::SeqCst);
+            let second_count = second_count.clone();
+            set_joystick_callback(move |_, _| {
+                second_count.fetch_add(1, Ordering::SeqCst);
+            });
+        });
+
+        joystick_callback(0, GLFWConnected);
+        joystick_callback(0, GLFWDisconnected);
+        assert_eq!((first.load(Ordering::SeqCst), second.load(Ordering::SeqCst)), (1, 1));
+
+        clear_joystick_callback();
+        joystick_callback(0, GLFWConnected);
+        assert_eq!(second.load(Ordering::SeqCst), 1);
+    }
+}
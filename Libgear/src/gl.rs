@@ -0,0 +1,116 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![allow(clippy::too_many_arguments, clippy::missing_safety_doc, clippy::type_complexity, clippy::unit_arg, clippy::unused_unit, clippy::let_unit_value)]
+use std::{ffi::c_void, mem::{size_of, transmute_copy}};
+
+use crate::error::*;
+
+include!(concat!(env!("OUT_DIR"), "/gl_bindings.rs"));
+
+#[cfg(feature = "gl-debug")]
+impl GLFunctions {
+    // Drains every pending error flag and reports the first one. The loop is bounded because a
+    // lost context can keep reporting errors forever.
+    fn check_error(&self, function: &'static str) -> Result<()> {
+        let Ok(get_error) = self.GetError.get() else { return Ok(()) };
+
+        let mut first = NO_ERROR;
+        for _ in 0..16 {
+            let code = get_error();
+            if code == NO_ERROR {
+                break;
+            }
+
+            log::error!(target: "gear::gl", "{} raised {}", function, gl_error_name(code));
+            if first == NO_ERROR {
+                first = code;
+            }
+        }
+
+        if first == NO_ERROR {
+            Ok(())
+        } else {
+            Err(GearError::Gl { function: function.into(), code: first })
+        }
+    }
+
+    // Routes KHR_debug messages through `log`. The caller checks that the context supports them.
+    pub(crate) fn install_debug_callback(&self) {
+        if !self.DebugMessageCallback.is_loaded() {
+            log::debug!(target: "gear::gl", "debug output is not available");
+            return;
+        }
+
+        unsafe {
+            let _ = self.Enable(DEBUG_OUTPUT);
+            let _ = self.Enable(DEBUG_OUTPUT_SYNCHRONOUS);
+            let _ = self.DebugMessageCallback(Some(debug_callback), std::ptr::null());
+        }
+    }
+}
+
+#[cfg(feature = "gl-debug")]
+extern "system" fn debug_callback(
+    source: types::GLenum,
+    gltype: types::GLenum,
+    id: types::GLuint,
+    severity: types::GLenum,
+    length: types::GLsizei,
+    message: *const types::GLchar,
+    _user: *mut c_void,
+) {
+    if message.is_null() {
+        return;
+    }
+
+    let message = unsafe { std::slice::from_raw_parts(message as *const u8, length.max(0) as usize) };
+    let level = match severity {
+        DEBUG_SEVERITY_HIGH => log::Level::Error,
+        DEBUG_SEVERITY_MEDIUM => log::Level::Warn,
+        DEBUG_SEVERITY_LOW => log::Level::Info,
+        _ => log::Level::Debug,
+    };
+
+    log::log!(
+        target: "gear::gl",
+        level,
+        "[source 0x{:X}, type 0x{:X}, id {}] {}",
+        source, gltype, id, String::from_utf8_lossy(message).trim_end()
+    );
+}
+
+/// A loaded GL function, or the name of one that couldn't be loaded.
+pub struct Proc<F> {
+    name: &'static str,
+    ptr: Option<F>,
+}
+
+impl<F: Copy> Proc<F> {
+    pub fn load_with<L>(name: &'static str, fallbacks: &[&'static str], loader: &mut L) -> Self
+    where
+        L: FnMut(&'static str) -> *const c_void,
+    {
+        assert_eq!(size_of::<F>(), size_of::<*const c_void>());
+
+        let ptr = std::iter::once(&name)
+            .chain(fallbacks)
+            .map(|symbol| loader(symbol))
+            .find(|ptr| !ptr.is_null());
+
+        Proc {
+            name,
+            ptr: ptr.map(|ptr| unsafe { transmute_copy::<*const c_void, F>(&ptr) }),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.ptr.is_some()
+    }
+
+    pub fn get(&self) -> Result<F> {
+        self.ptr.ok_or_else(|| GearError::MissingSymbol(self.name.to_string()))
+    }
+}
@@ -0,0 +1,225 @@
+use std::{ffi::c_void, io::Cursor, path::Path};
+
+use ::image::{DynamicImage, ExtendedColorType, ImageFormat};
+
+use crate::{context::GlContext, error::*, gl::{self, types::*, GLFunctions}, texture::*};
+
+/// 8-bit pixels in row-major order, top row first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, format: PixelFormat, pixels: Vec<u8>) -> Result<Self> {
+        if !matches!(format, PixelFormat::R8 | PixelFormat::Rgb8 | PixelFormat::Rgba8) {
+            return Err(GearError::InvalidArgument(format!("images can't hold {:?} pixels", format)));
+        }
+
+        let expected = width as usize * height as usize * format.bytes_per_pixel();
+        if pixels.len() != expected {
+            return Err(GearError::InvalidArgument(format!(
+                "{}x{} {:?} image needs {} bytes, got {}",
+                width, height, format, expected, pixels.len()
+            )));
+        }
+
+        Ok(Image { width, height, format, pixels })
+    }
+
+    /// Decodes PNG, JPEG, BMP or TGA. The format is guessed from the contents; TGA files have no
+    /// signature, so for them the extension decides.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let bytes = std::fs::read(path.as_ref())
+            .map_err(|error| GearError::Image(format!("{}: {}", path.as_ref().display(), error)))?;
+        decode(&bytes, ImageFormat::from_path(path).ok())
+    }
+
+    /// Anything that isn't recognized as PNG, JPEG or BMP is decoded as TGA.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        decode(bytes, None)
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        ::image::save_buffer_with_format(
+            path,
+            &self.pixels,
+            self.width,
+            self.height,
+            color_type(self.format),
+            ImageFormat::Png,
+        )?;
+        Ok(())
+    }
+
+    pub fn encode_png(&self) -> Result<Vec<u8>> {
+        let mut bytes = Cursor::new(Vec::new());
+        ::image::write_buffer_with_format(
+            &mut bytes,
+            &self.pixels,
+            self.width,
+            self.height,
+            color_type(self.format),
+            ImageFormat::Png,
+        )?;
+        Ok(bytes.into_inner())
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// GL puts the first row at the bottom, so images read back from it come out upside down.
+    pub fn flip_vertically(&mut self) {
+        let row = self.width as usize * self.format.bytes_per_pixel();
+        if row == 0 {
+            return;
+        }
+
+        let rows = self.pixels.len() / row;
+        for top in 0..rows / 2 {
+            let (upper, lower) = self.pixels.split_at_mut((rows - top - 1) * row);
+            upper[top * row..(top + 1) * row].swap_with_slice(&mut lower[..row]);
+        }
+    }
+}
+
+impl From<DynamicImage> for Image {
+    fn from(image: DynamicImage) -> Self {
+        let (width, height) = (image.width(), image.height());
+
+        let (format, pixels) = match image {
+            DynamicImage::ImageLuma8(image) => (PixelFormat::R8, image.into_raw()),
+            DynamicImage::ImageRgb8(image) => (PixelFormat::Rgb8, image.into_raw()),
+            DynamicImage::ImageRgba8(image) => (PixelFormat::Rgba8, image.into_raw()),
+            image => (PixelFormat::Rgba8, image.into_rgba8().into_raw()),
+        };
+
+        Image { width, height, format, pixels }
+    }
+}
+
+impl Texture2D {
+    /// Textures are uploaded bottom row first like every other GL image, so the image is flipped
+    /// on the way in and comes back the right way up from `read_pixels`.
+    pub fn from_image(context: &GlContext, image: &Image) -> Result<Self> {
+        let width = GLsizei::try_from(image.width())
+            .map_err(|_| GearError::InvalidArgument("image is too wide for a texture".into()))?;
+        let height = GLsizei::try_from(image.height())
+            .map_err(|_| GearError::InvalidArgument("image is too tall for a texture".into()))?;
+
+        let mut flipped = image.clone();
+        flipped.flip_vertically();
+        Texture2D::from_pixels(context, width, height, flipped.format(), flipped.pixels())
+    }
+}
+
+fn decode(bytes: &[u8], hint: Option<ImageFormat>) -> Result<Image> {
+    let format = ::image::guess_format(bytes).ok().or(hint).unwrap_or(ImageFormat::Tga);
+    Ok(Image::from(::image::load_from_memory_with_format(bytes, format)?))
+}
+
+// Reads RGBA8 pixels from the bound read framebuffer and flips them so the top row comes first.
+pub(crate) unsafe fn read_pixels(gl: &GLFunctions, width: GLsizei, height: GLsizei) -> Result<Image> {
+    let mut pixels = vec![0u8; width.max(0) as usize * height.max(0) as usize * 4];
+    gl.PixelStorei(gl::PACK_ALIGNMENT, 1)?;
+    gl.ReadPixels(0, 0, width, height, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut c_void)?;
+
+    let mut image = Image::new(width as u32, height as u32, PixelFormat::Rgba8, pixels)?;
+    image.flip_vertically();
+    Ok(image)
+}
+
+fn color_type(format: PixelFormat) -> ExtendedColorType {
+    match format {
+        PixelFormat::R8 => ExtendedColorType::L8,
+        PixelFormat::Rgb8 => ExtendedColorType::Rgb8,
+        _ => ExtendedColorType::Rgba8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(image: &Image, format: ImageFormat) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        ::image::write_buffer_with_format(
+            &mut bytes,
+            image.pixels(),
+            image.width(),
+            image.height(),
+            color_type(image.format()),
+            format,
+        )
+        .unwrap();
+        bytes.into_inner()
+    }
+
+    fn checkerboard(format: PixelFormat) -> Image {
+        let pixels = (0..4 * 3)
+            .flat_map(|index| {
+                let value = if (index % 4 + index / 4) % 2 == 0 { 255 } else { 0 };
+                vec![value; format.bytes_per_pixel()]
+            })
+            .collect();
+        Image::new(4, 3, format, pixels).unwrap()
+    }
+
+    #[test]
+    fn lossless_formats_round_trip() {
+        for format in [ImageFormat::Png, ImageFormat::Bmp, ImageFormat::Tga] {
+            let image = checkerboard(PixelFormat::Rgba8);
+            assert_eq!(Image::from_bytes(&encode(&image, format)).unwrap(), image, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn jpeg_round_trips_approximately() {
+        let image = checkerboard(PixelFormat::Rgb8);
+        let decoded = Image::from_bytes(&encode(&image, ImageFormat::Jpeg)).unwrap();
+
+        assert_eq!((decoded.width(), decoded.height(), decoded.format()), (4, 3, PixelFormat::Rgb8));
+        // Hard black and white edges ring a little, but every pixel stays on its side.
+        for (decoded, original) in decoded.pixels().iter().zip(image.pixels()) {
+            assert!(decoded.abs_diff(*original) < 128, "{} vs {}", decoded, original);
+        }
+    }
+
+    #[test]
+    fn tga_loads_by_extension() {
+        let image = checkerboard(PixelFormat::Rgb8);
+        let path = std::env::temp_dir().join(format!("gear-{}.tga", std::process::id()));
+        std::fs::write(&path, encode(&image, ImageFormat::Tga)).unwrap();
+
+        let loaded = Image::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), image);
+    }
+
+    #[test]
+    fn flip_reverses_rows() {
+        let mut image = Image::new(2, 3, PixelFormat::R8, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        image.flip_vertically();
+        assert_eq!(image.pixels(), [5, 6, 3, 4, 1, 2]);
+    }
+}
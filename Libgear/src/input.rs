@@ -0,0 +1,370 @@
+use std::{ffi::c_int, ops::BitOr};
+
+use crate::{error::*, types::*, window::{release_glfw, retain_glfw}};
+use super::{glfwCreateCursor, glfwCreateStandardCursor, glfwDestroyCursor};
+
+macro_rules! keys {
+    ($($name:ident = $value:expr,)*) => {
+        #[repr(i32)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Key {
+            Unknown = -1,
+            $($name = $value,)*
+        }
+
+        impl Key {
+            pub const ALL: &'static [Key] = &[$(Key::$name,)*];
+
+            pub fn from_raw(key: c_int) -> Key {
+                match key {
+                    $($value => Key::$name,)*
+                    _ => Key::Unknown,
+                }
+            }
+        }
+    };
+}
+
+keys! {
+    Space = 32,
+    Apostrophe = 39,
+    Comma = 44,
+    Minus = 45,
+    Period = 46,
+    Slash = 47,
+    Num0 = 48,
+    Num1 = 49,
+    Num2 = 50,
+    Num3 = 51,
+    Num4 = 52,
+    Num5 = 53,
+    Num6 = 54,
+    Num7 = 55,
+    Num8 = 56,
+    Num9 = 57,
+    Semicolon = 59,
+    Equal = 61,
+    A = 65,
+    B = 66,
+    C = 67,
+    D = 68,
+    E = 69,
+    F = 70,
+    G = 71,
+    H = 72,
+    I = 73,
+    J = 74,
+    K = 75,
+    L = 76,
+    M = 77,
+    N = 78,
+    O = 79,
+    P = 80,
+    Q = 81,
+    R = 82,
+    S = 83,
+    T = 84,
+    U = 85,
+    V = 86,
+    W = 87,
+    X = 88,
+    Y = 89,
+    Z = 90,
+    LeftBracket = 91,
+    Backslash = 92,
+    RightBracket = 93,
+    GraveAccent = 96,
+    World1 = 161,
+    World2 = 162,
+    Escape = 256,
+    Enter = 257,
+    Tab = 258,
+    Backspace = 259,
+    Insert = 260,
+    Delete = 261,
+    Right = 262,
+    Left = 263,
+    Down = 264,
+    Up = 265,
+    PageUp = 266,
+    PageDown = 267,
+    Home = 268,
+    End = 269,
+    CapsLock = 280,
+    ScrollLock = 281,
+    NumLock = 282,
+    PrintScreen = 283,
+    Pause = 284,
+    F1 = 290,
+    F2 = 291,
+    F3 = 292,
+    F4 = 293,
+    F5 = 294,
+    F6 = 295,
+    F7 = 296,
+    F8 = 297,
+    F9 = 298,
+    F10 = 299,
+    F11 = 300,
+    F12 = 301,
+    F13 = 302,
+    F14 = 303,
+    F15 = 304,
+    F16 = 305,
+    F17 = 306,
+    F18 = 307,
+    F19 = 308,
+    F20 = 309,
+    F21 = 310,
+    F22 = 311,
+    F23 = 312,
+    F24 = 313,
+    F25 = 314,
+    Kp0 = 320,
+    Kp1 = 321,
+    Kp2 = 322,
+    Kp3 = 323,
+    Kp4 = 324,
+    Kp5 = 325,
+    Kp6 = 326,
+    Kp7 = 327,
+    Kp8 = 328,
+    Kp9 = 329,
+    KpDecimal = 330,
+    KpDivide = 331,
+    KpMultiply = 332,
+    KpSubtract = 333,
+    KpAdd = 334,
+    KpEnter = 335,
+    KpEqual = 336,
+    LeftShift = 340,
+    LeftControl = 341,
+    LeftAlt = 342,
+    LeftSuper = 343,
+    RightShift = 344,
+    RightControl = 345,
+    RightAlt = 346,
+    RightSuper = 347,
+    Menu = 348,
+}
+
+impl Key {
+    pub fn raw(self) -> c_int {
+        self as c_int
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Release,
+    Press,
+    Repeat,
+}
+
+impl Action {
+    pub fn from_raw(action: c_int) -> Action {
+        if action == GLFWPress {
+            Action::Press
+        } else if action == GLFWRepeat {
+            Action::Repeat
+        } else {
+            Action::Release
+        }
+    }
+
+    pub fn raw(self) -> c_int {
+        match self {
+            Action::Release => GLFWRelease,
+            Action::Press => GLFWPress,
+            Action::Repeat => GLFWRepeat,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers(c_int);
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers(0);
+    pub const SHIFT: Modifiers = Modifiers(0x0001);
+    pub const CONTROL: Modifiers = Modifiers(0x0002);
+    pub const ALT: Modifiers = Modifiers(0x0004);
+    pub const SUPER: Modifiers = Modifiers(0x0008);
+    pub const CAPS_LOCK: Modifiers = Modifiers(0x0010);
+    pub const NUM_LOCK: Modifiers = Modifiers(0x0020);
+
+    pub fn from_raw(mods: c_int) -> Modifiers {
+        Modifiers(mods)
+    }
+
+    pub fn raw(self) -> c_int {
+        self.0
+    }
+
+    pub fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, rhs: Modifiers) -> Modifiers {
+        Modifiers(self.0 | rhs.0)
+    }
+}
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left = 0,
+    Right = 1,
+    Middle = 2,
+    Button4 = 3,
+    Button5 = 4,
+    Button6 = 5,
+    Button7 = 6,
+    Button8 = 7,
+}
+
+impl MouseButton {
+    pub const ALL: &'static [MouseButton] = &[
+        MouseButton::Left,
+        MouseButton::Right,
+        MouseButton::Middle,
+        MouseButton::Button4,
+        MouseButton::Button5,
+        MouseButton::Button6,
+        MouseButton::Button7,
+        MouseButton::Button8,
+    ];
+
+    pub fn from_raw(button: c_int) -> Option<MouseButton> {
+        MouseButton::ALL.get(usize::try_from(button).ok()?).copied()
+    }
+
+    pub fn raw(self) -> c_int {
+        self as c_int
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CursorMode {
+    Normal,
+    Hidden,
+    Disabled,
+}
+
+impl CursorMode {
+    pub fn from_raw(mode: c_int) -> CursorMode {
+        if mode == GLFWCursorHidden {
+            CursorMode::Hidden
+        } else if mode == GLFWCursorDisabled {
+            CursorMode::Disabled
+        } else {
+            CursorMode::Normal
+        }
+    }
+
+    pub fn raw(self) -> c_int {
+        match self {
+            CursorMode::Normal => GLFWCursorNormal,
+            CursorMode::Hidden => GLFWCursorHidden,
+            CursorMode::Disabled => GLFWCursorDisabled,
+        }
+    }
+}
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StandardCursor {
+    Arrow = 0x00036001,
+    IBeam = 0x00036002,
+    Crosshair = 0x00036003,
+    Hand = 0x00036004,
+    HorizontalResize = 0x00036005,
+    VerticalResize = 0x00036006,
+}
+
+impl StandardCursor {
+    pub fn from_raw(shape: c_int) -> Option<StandardCursor> {
+        [
+            StandardCursor::Arrow,
+            StandardCursor::IBeam,
+            StandardCursor::Crosshair,
+            StandardCursor::Hand,
+            StandardCursor::HorizontalResize,
+            StandardCursor::VerticalResize,
+        ].into_iter().find(|cursor| *cursor as c_int == shape)
+    }
+}
+
+// Bytes of RGBA pixels in a cursor image, rejecting empty images and sizes that overflow `usize`.
+pub(crate) fn cursor_size(width: c_int, height: c_int) -> Result<usize> {
+    if width <= 0 || height <= 0 {
+        return Err(GearError::InvalidArgument(format!("cursor image of {}x{} is empty", width, height)));
+    }
+
+    (width as usize).checked_mul(height as usize).and_then(|pixels| pixels.checked_mul(4)).ok_or_else(|| {
+        GearError::InvalidArgument(format!("cursor image of {}x{} is too large", width, height))
+    })
+}
+
+/// A cursor image for `Window::set_cursor`.
+pub struct Cursor {
+    handle: *mut GLFWcursor,
+}
+
+impl Cursor {
+    /// GLFW has to be initialized, so create cursors after the first window. They keep it
+    /// initialized until they are dropped, even if every window closes first.
+    pub fn standard(shape: StandardCursor) -> Result<Cursor> {
+        let handle = unsafe { glfwCreateStandardCursor(shape as c_int) };
+        if handle.is_null() {
+            return Err(GearError::InvalidArgument(format!("{:?} cursor is not available", shape)));
+        }
+
+        retain_glfw();
+        Ok(Cursor { handle })
+    }
+
+    pub fn from_rgba(width: c_int, height: c_int, pixels: &[u8], hot_x: c_int, hot_y: c_int) -> Result<Cursor> {
+        let size = cursor_size(width, height)?;
+        if pixels.len() != size {
+            return Err(GearError::InvalidArgument(format!(
+                "cursor image of {}x{} needs {} bytes of RGBA pixels, got {}",
+                width, height, size, pixels.len()
+            )));
+        }
+
+        let image = GLFWimage {
+            width,
+            height,
+            pixels: pixels.as_ptr() as *mut u8,
+        };
+        let handle = unsafe { glfwCreateCursor(&image, hot_x, hot_y) };
+        if handle.is_null() {
+            return Err(GearError::InvalidArgument("failed to create cursor".into()));
+        }
+
+        retain_glfw();
+        Ok(Cursor { handle })
+    }
+
+    pub fn handle(&self) -> *mut GLFWcursor {
+        self.handle
+    }
+}
+
+impl Drop for Cursor {
+    fn drop(&mut self) {
+        unsafe {
+            glfwDestroyCursor(self.handle);
+            release_glfw();
+        }
+    }
+}
@@ -1,17 +1,69 @@
+//! Windows, input and OpenGL resources on top of GLFW.
+//!
+//! A [`Window`] owns a [`GlContext`], which every resource (buffers, shader programs, vertex
+//! arrays, textures, framebuffers) is created against. The same library is also usable from C
+//! through the API declared in `include/gear.h`.
 #![allow(non_snake_case)]
-use core::panic;
-use std::{ffi::*, ptr::{null_mut, null}};
+use std::ffi::*;
 
+mod capi;
 mod types;
-mod functions;
-mod shader;
-use functions::*;
+/// Vertex, index and uniform buffers.
+pub mod buffer;
+/// The function table of an OpenGL context and which thread may use it.
+pub mod context;
+/// Draw calls.
+pub mod draw;
+/// The crate's error type.
+pub mod error;
+/// Window events.
+pub mod event;
+/// Offscreen render targets.
+pub mod framebuffer;
+/// Joysticks and gamepads.
+pub mod gamepad;
+/// Generated OpenGL bindings.
+pub mod gl;
+/// Decoding, encoding and reading back images.
+pub mod image;
+/// Keys, mouse buttons and cursors.
+pub mod input;
+/// A recording stand-in for the GL driver, for tests.
+#[cfg(feature = "mock")]
+pub mod mock;
+/// Shader programs.
+pub mod shader;
+/// Textures and their sampling state.
+pub mod texture;
+/// Values that can be set as shader uniforms.
+pub mod uniform;
+/// Vertex arrays and vertex layouts.
+pub mod vertex;
+/// Windows and their event loop.
+pub mod window;
 use types::*;
 
+pub use buffer::{Buffer, BufferTarget, BufferUsage};
+pub use context::GlContext;
+pub use draw::*;
+pub use error::{GearError, Result};
+pub use event::Event;
+pub use framebuffer::{Attachment, BufferMask, Framebuffer, Renderbuffer, RenderbufferFormat};
+pub use gamepad::*;
+pub use image::Image;
+pub use input::*;
+pub use shader::{ShaderProgram, ShaderProgramBuilder, ShaderStage};
+pub use texture::*;
+pub use types::{GLFWcursor, GLFWmonitor, GLFWwindow};
+pub use uniform::{Sampler, Uniform};
+pub use vertex::*;
+pub use window::*;
+pub use bytemuck;
+
 extern "C" {
     fn glfwInit() -> c_int;
 
-    pub fn glfwCreateWindow(
+    fn glfwCreateWindow(
         width: c_int,
         height: c_int,
         title: *const c_char,
@@ -19,18 +71,47 @@ extern "C" {
         share: *mut GLFWwindow,
     ) -> *mut GLFWwindow;
 
+    fn glfwDefaultWindowHints();
+
+    fn glfwWindowHint(hint: c_int, value: c_int);
+
+    fn glfwGetPrimaryMonitor() -> *mut GLFWmonitor;
+
     fn glfwWindowShouldClose(window: *mut GLFWwindow) -> c_int;
 
     fn glfwMakeContextCurrent(window: *mut GLFWwindow);
+
+    fn glfwGetCurrentContext() -> *mut GLFWwindow;
     
     fn glfwSwapInterval(interval: c_int);
 
     fn glfwTerminate();
 
+    fn glfwDestroyWindow(window: *mut GLFWwindow);
+
+    fn glfwGetTime() -> c_double;
+
     fn glfwSetFramebufferSizeCallback(
         window: *mut GLFWwindow,
-        cbfun: GLFWframebuffersizefun,
-    );
+        cbfun: Option<GLFWframebuffersizefun>,
+    ) -> Option<GLFWframebuffersizefun>;
+
+    fn glfwSetWindowSizeCallback(window: *mut GLFWwindow, cbfun: Option<GLFWwindowsizefun>) -> Option<GLFWwindowsizefun>;
+
+    fn glfwSetWindowFocusCallback(window: *mut GLFWwindow, cbfun: Option<GLFWwindowfocusfun>) -> Option<GLFWwindowfocusfun>;
+
+    fn glfwSetWindowIconifyCallback(window: *mut GLFWwindow, cbfun: Option<GLFWwindowiconifyfun>) -> Option<GLFWwindowiconifyfun>;
+
+    fn glfwSetWindowCloseCallback(window: *mut GLFWwindow, cbfun: Option<GLFWwindowclosefun>) -> Option<GLFWwindowclosefun>;
+
+    fn glfwSetDropCallback(window: *mut GLFWwindow, cbfun: Option<GLFWdropfun>) -> Option<GLFWdropfun>;
+
+    fn glfwSetWindowContentScaleCallback(
+        window: *mut GLFWwindow,
+        cbfun: Option<GLFWwindowcontentscalefun>,
+    ) -> Option<GLFWwindowcontentscalefun>;
+
+    fn glfwGetFramebufferSize(window: *mut GLFWwindow, width: *mut c_int, height: *mut c_int);
 
     fn glfwSwapBuffers(window: *mut GLFWwindow);
 
@@ -40,110 +121,106 @@ extern "C" {
 
     fn glfwSetWindowShouldClose(window: *mut GLFWwindow, value: c_int);
 
-    fn glfwGetProcAddress(procname: *const c_char) -> GLFWglproc;
-}
+    fn glfwSetWindowUserPointer(window: *mut GLFWwindow, pointer: *mut c_void);
 
-#[allow(non_upper_case_globals)]
-static mut glfunctions: Option<GLFunctions> = None;
+    fn glfwGetWindowUserPointer(window: *mut GLFWwindow) -> *mut c_void;
 
-#[no_mangle]
-extern "C" fn run(
-    title: *const c_char
-) {
-    unsafe {
-        if glfwInit() == 0 {
-            panic!("Fail to initialize the library. Exit Code: 0");
-        };
+    fn glfwSetKeyCallback(window: *mut GLFWwindow, cbfun: Option<GLFWkeyfun>) -> Option<GLFWkeyfun>;
 
-        let window = glfwCreateWindow(500, 500, title, null_mut(), null_mut());
+    fn glfwSetCharCallback(window: *mut GLFWwindow, cbfun: Option<GLFWcharfun>) -> Option<GLFWcharfun>;
 
-        println!("{:?}", &CStr::from_ptr(title).to_str().unwrap());
+    fn glfwGetCursorPos(window: *mut GLFWwindow, xpos: *mut c_double, ypos: *mut c_double);
 
-        if window.is_null() {
-            println!("Failed to create window. Error Code: 2");
-            glfwTerminate();
-            return;
-        }
+    fn glfwSetCursorPos(window: *mut GLFWwindow, xpos: c_double, ypos: c_double);
 
-        glfwMakeContextCurrent(window);
+    fn glfwGetMouseButton(window: *mut GLFWwindow, button: c_int) -> c_int;
 
-        glfunctions = Some(GLFunctions::new());
-        let vertexShadersource = CString::new(shader::vertex).unwrap();
+    fn glfwGetInputMode(window: *mut GLFWwindow, mode: c_int) -> c_int;
 
-        glfwSetFramebufferSizeCallback(window, frameBufferSizeCallBack);
+    fn glfwSetInputMode(window: *mut GLFWwindow, mode: c_int, value: c_int);
 
-        let verteices: [f32; 9] = [
-            -0.5, -0.5, 0.,
-            0.5, 0.5, 0.,
-            0., 0.5, 0.
-        ];
+    fn glfwRawMouseMotionSupported() -> c_int;
 
-        while glfwWindowShouldClose(window) == 0 {
+    fn glfwCreateCursor(image: *const GLFWimage, xhot: c_int, yhot: c_int) -> *mut GLFWcursor;
 
-            if glfwGetKey(window, GLFWKeyEscape) == GLFWPress {
-                glfwSetWindowShouldClose(window, True);
-            }
+    fn glfwCreateStandardCursor(shape: c_int) -> *mut GLFWcursor;
 
-            glfunctions.as_ref().unwrap().clearcolor.run(0.2, 0.3, 0.3, 1.0);
-
-            let mut VBO: GLuint = 0;
-            glfunctions.as_ref().unwrap().genbuffers.run(1, VBO as *mut GLuint);
-            glfunctions.as_ref().unwrap().bindbuffers.run(glArrayBuffer, VBO);
-            glfunctions.as_ref().unwrap().bufferdata.run(
-                glArrayBuffer, 
-                verteices.len() as GLsizeiptr, &verteices[0] as *const f32 as *const c_void, 
-                glStaticDraw
-            );
-            let vertexShader = glfunctions.as_ref().unwrap().createShader.run(glVertexShader);
-            glfunctions.as_ref().unwrap().shaderSource.run(
-                vertexShader, 
-                1, 
-                vertexShadersource.as_ptr() as *mut GLchar, 
-                null::<GLint>() as *mut _
-            );
-            glfunctions.as_ref().unwrap().compileShader.run(vertexShader);
-
-            glfunctions.as_ref().unwrap().clear.run(glColorBufferBit);
-
-            glfwSwapBuffers(window);
-            glfwPollEvents(); 
-        }
+    fn glfwDestroyCursor(cursor: *mut GLFWcursor);
 
-        println!("Library closed successfully. Exit Code: 1");
-        glfwTerminate();
-    }
-}
+    fn glfwSetCursor(window: *mut GLFWwindow, cursor: *mut GLFWcursor);
 
-extern "C" fn frameBufferSizeCallBack(window: *mut GLFWwindow, width: GLint, height: GLint) {
-    unsafe {
-        glfunctions.as_ref().unwrap().viewport.run(0, 0, width, height);
-    }
-}
+    fn glfwSetCursorPosCallback(window: *mut GLFWwindow, cbfun: Option<GLFWcursorposfun>) -> Option<GLFWcursorposfun>;
 
-struct GLFunctions {
-    viewport: Viewport,
-    clearcolor: ClearColor,
-    clear: Clear,
-    genbuffers: GenBuffers,
-    bindbuffers: BindBuffer,
-    bufferdata: BufferData,
-    createShader: CreateShader,
-    shaderSource: ShaderSource,
-    compileShader: CompileShader
+    fn glfwSetCursorEnterCallback(window: *mut GLFWwindow, cbfun: Option<GLFWcursorenterfun>) -> Option<GLFWcursorenterfun>;
+
+    fn glfwSetMouseButtonCallback(window: *mut GLFWwindow, cbfun: Option<GLFWmousebuttonfun>) -> Option<GLFWmousebuttonfun>;
+
+    fn glfwJoystickPresent(jid: c_int) -> c_int;
+
+    fn glfwJoystickIsGamepad(jid: c_int) -> c_int;
+
+    fn glfwGetJoystickName(jid: c_int) -> *const c_char;
+
+    fn glfwGetGamepadName(jid: c_int) -> *const c_char;
+
+    fn glfwGetJoystickGUID(jid: c_int) -> *const c_char;
+
+    fn glfwGetJoystickAxes(jid: c_int, count: *mut c_int) -> *const c_float;
+
+    fn glfwGetJoystickButtons(jid: c_int, count: *mut c_int) -> *const c_uchar;
+
+    fn glfwGetGamepadState(jid: c_int, state: *mut GamepadState) -> c_int;
+
+    fn glfwUpdateGamepadMappings(string: *const c_char) -> c_int;
+
+    fn glfwSetJoystickCallback(cbfun: Option<GLFWjoystickfun>) -> Option<GLFWjoystickfun>;
+
+    fn glfwSetScrollCallback(window: *mut GLFWwindow, cbfun: Option<GLFWscrollfun>) -> Option<GLFWscrollfun>;
+
+    fn glfwGetProcAddress(procname: *const c_char) -> GLFWglproc;
+
+    #[cfg(feature = "gl-debug")]
+    fn glfwGetWindowAttrib(window: *mut GLFWwindow, attrib: c_int) -> c_int;
+
+    #[cfg(feature = "gl-debug")]
+    fn glfwExtensionSupported(extension: *const c_char) -> c_int;
 }
 
-impl GLFunctions {
-    fn new() -> Self {
-        GLFunctions { 
-            viewport: Viewport::new(), 
-            clearcolor: ClearColor::new(),
-            clear: Clear::new(),
-            genbuffers: GenBuffers::new(),
-            bindbuffers: BindBuffer::new(),
-            bufferdata: BufferData::new(),
-            createShader: CreateShader::new(),
-            shaderSource: ShaderSource::new(),
-            compileShader: CompileShader::new()
+/// Opens a window and draws a triangle into it until it is closed.
+pub fn run_window(config: &WindowConfig) -> Result<()> {
+    let window = Window::new(config)?;
+
+    let verteices: [f32; 9] = [
+        -0.5, -0.5, 0.,
+        0.5, 0.5, 0.,
+        0., 0.5, 0.
+    ];
+
+    let context = window.context();
+
+    let program = ShaderProgram::builder()
+        .vertex(shader::vertex)
+        .fragment(shader::fragment)
+        .build(context)?;
+    program.set_uniform("color", [1.0, 0.5, 0.2, 1.0])?;
+    let vbo = Buffer::from_slice(context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &verteices)?;
+    let mut vao = VertexArray::new(context)?;
+    vao.bind_buffer(&vbo, &VertexLayout::new().attribute(0, 3, AttribType::Float, false))?;
+
+    window.run_with(|frame| {
+        for event in frame.window().events() {
+            if let Event::Key { key: Key::Escape, action: Action::Press, .. } = event {
+                frame.close();
+            }
         }
-    }
-}
\ No newline at end of file
+
+        context.clear(BufferMask::COLOR, [0.2, 0.3, 0.3, 1.0])?;
+
+        draw_arrays(&program, &vao, Primitive::Triangles, ..)
+    })
+}
+
+pub(crate) fn glfw_loader(name: &'static str) -> *const c_void {
+    let procname = CString::new(name).unwrap();
+    unsafe { glfwGetProcAddress(procname.as_ptr()) }
+}
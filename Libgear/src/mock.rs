@@ -0,0 +1,224 @@
+#![allow(non_snake_case, clippy::too_many_arguments, clippy::unused_unit)]
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::{context::GlContext, gl::{self, types}};
+
+include!(concat!(env!("OUT_DIR"), "/gl_mock.rs"));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MockArg {
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+    Pointer(usize),
+    Function(bool),
+}
+
+impl MockArg {
+    /// Integers of either sign as i64, so tests don't have to care how GL typed the parameter.
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            MockArg::Int(value) => Some(value),
+            MockArg::Uint(value) => i64::try_from(value).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match *self {
+            MockArg::Float(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_pointer(&self) -> Option<usize> {
+        match *self {
+            MockArg::Pointer(address) => Some(address),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub name: &'static str,
+    pub args: Vec<MockArg>,
+}
+
+impl Call {
+    pub fn arg(&self, index: usize) -> Option<MockArg> {
+        self.args.get(index).copied()
+    }
+}
+
+type Handler = Box<dyn FnMut(&[MockArg]) -> u64>;
+
+#[derive(Default)]
+struct MockState {
+    calls: Vec<Call>,
+    handlers: HashMap<&'static str, Handler>,
+    last_name: u32,
+}
+
+impl MockState {
+    fn next_name(&mut self) -> u32 {
+        self.last_name += 1;
+        self.last_name
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<MockState> = RefCell::new(MockState::default());
+}
+
+/// A context whose every GL function records its call on this thread instead of reaching a driver.
+/// Without a handler, object names count up from 1, shaders compile and link, framebuffers are
+/// complete and everything else returns 0.
+pub fn context() -> GlContext {
+    GlContext::load_with(loader)
+}
+
+/// Every call recorded on this thread so far.
+pub fn calls() -> Vec<Call> {
+    STATE.with(|state| state.borrow().calls.clone())
+}
+
+/// Returns the recorded calls and starts a new recording.
+pub fn take_calls() -> Vec<Call> {
+    STATE.with(|state| std::mem::take(&mut state.borrow_mut().calls))
+}
+
+/// The recorded calls to `name`, e.g. "glBufferData".
+pub fn calls_to(name: &str) -> Vec<Call> {
+    STATE.with(|state| state.borrow().calls.iter().filter(|call| call.name == name).cloned().collect())
+}
+
+/// Runs `handler` whenever `name` (e.g. "glGetShaderiv") is called. Its result becomes the return
+/// value, and pointer arguments can be written through to fake out-parameters.
+pub fn on<F: FnMut(&[MockArg]) -> u64 + 'static>(name: &'static str, handler: F) {
+    STATE.with(|state| state.borrow_mut().handlers.insert(name, Box::new(handler)));
+}
+
+/// Forgets the recorded calls, the handlers and the object names handed out.
+pub fn reset() {
+    STATE.with(|state| *state.borrow_mut() = MockState::default());
+}
+
+fn record(name: &'static str, args: Vec<MockArg>) -> u64 {
+    // The handler is taken out while it runs so it can call `calls` or `on` itself.
+    let handler = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.calls.push(Call { name, args: args.clone() });
+        state.handlers.remove(name)
+    });
+
+    let Some(mut handler) = handler else {
+        return STATE.with(|state| unsafe { default(&mut state.borrow_mut(), name, &args) });
+    };
+    let result = handler(&args);
+    STATE.with(|state| {
+        state.borrow_mut().handlers.entry(name).or_insert(handler);
+    });
+    result
+}
+
+// The pointers are the out-parameters of a real call made through `GLFunctions`.
+unsafe fn default(state: &mut MockState, name: &str, args: &[MockArg]) -> u64 {
+    match name {
+        "glCreateShader" | "glCreateProgram" | "glCreateShaderProgramv" => state.next_name() as u64,
+        "glGetShaderiv" | "glGetProgramiv" => {
+            let pname = args[1].as_int().unwrap_or(0) as types::GLenum;
+            if pname == gl::COMPILE_STATUS || pname == gl::LINK_STATUS {
+                write(args[2], gl::TRUE as types::GLint);
+            }
+            0
+        }
+        "glCheckFramebufferStatus" | "glCheckNamedFramebufferStatus" => gl::FRAMEBUFFER_COMPLETE as u64,
+        // glGenBuffers(n, names), glCreateTextures(target, n, names) and the like.
+        _ if (name.starts_with("glGen") && !name.starts_with("glGenerate")) || name.starts_with("glCreate") => {
+            if let [.., count, MockArg::Pointer(names)] = *args {
+                let count = if names == 0 { 0 } else { count.as_int().unwrap_or(0).max(0) as usize };
+                for index in 0..count {
+                    *(names as *mut types::GLuint).add(index) = state.next_name();
+                }
+            }
+            0
+        }
+        _ => 0,
+    }
+}
+
+unsafe fn write<T>(arg: MockArg, value: T) {
+    if let Some(address) = arg.as_pointer().filter(|address| *address != 0) {
+        *(address as *mut T) = value;
+    }
+}
+
+trait IntoMockArg {
+    fn into_mock_arg(self) -> MockArg;
+}
+
+macro_rules! mock_arg {
+    ($variant:ident as $wide:ty: $($ty:ty),*) => {
+        $(impl IntoMockArg for $ty {
+            fn into_mock_arg(self) -> MockArg {
+                MockArg::$variant(self as $wide)
+            }
+        })*
+    };
+}
+
+mock_arg!(Int as i64: i8, i16, i32, i64, isize);
+mock_arg!(Uint as u64: u8, u16, u32, u64, usize);
+mock_arg!(Float as f64: f32, f64);
+
+impl<T> IntoMockArg for *const T {
+    fn into_mock_arg(self) -> MockArg {
+        MockArg::Pointer(self as usize)
+    }
+}
+
+impl<T> IntoMockArg for *mut T {
+    fn into_mock_arg(self) -> MockArg {
+        MockArg::Pointer(self as usize)
+    }
+}
+
+// Debug callbacks are only recorded as being set or cleared.
+impl<F> IntoMockArg for Option<F> {
+    fn into_mock_arg(self) -> MockArg {
+        MockArg::Function(self.is_some())
+    }
+}
+
+trait MockReturn {
+    fn from_bits(bits: u64) -> Self;
+}
+
+macro_rules! mock_return {
+    ($($ty:ty),*) => {
+        $(impl MockReturn for $ty {
+            fn from_bits(bits: u64) -> Self {
+                bits as $ty
+            }
+        })*
+    };
+}
+
+mock_return!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl MockReturn for () {
+    fn from_bits(_bits: u64) -> Self {}
+}
+
+impl<T> MockReturn for *const T {
+    fn from_bits(bits: u64) -> Self {
+        bits as usize as *const T
+    }
+}
+
+impl<T> MockReturn for *mut T {
+    fn from_bits(bits: u64) -> Self {
+        bits as usize as *mut T
+    }
+}
@@ -1,3 +1,217 @@
+#![allow(non_upper_case_globals)]
+use std::{cell::RefCell, collections::HashMap, ffi::CString, fmt, ptr::{null, null_mut}};
+
+use crate::{context::GlContext, error::*, gl::{self, types::*, GLFunctions}, uniform::Uniform};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+    Geometry,
+    Link,
+}
+
+impl ShaderStage {
+    pub fn gl(self) -> Option<GLenum> {
+        match self {
+            ShaderStage::Vertex => Some(gl::VERTEX_SHADER),
+            ShaderStage::Fragment => Some(gl::FRAGMENT_SHADER),
+            ShaderStage::Geometry => Some(gl::GEOMETRY_SHADER),
+            ShaderStage::Link => None,
+        }
+    }
+}
+
+impl fmt::Display for ShaderStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderStage::Vertex => write!(f, "vertex"),
+            ShaderStage::Fragment => write!(f, "fragment"),
+            ShaderStage::Geometry => write!(f, "geometry"),
+            ShaderStage::Link => write!(f, "link"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ShaderProgramBuilder {
+    vertex: Option<String>,
+    fragment: Option<String>,
+    geometry: Option<String>,
+}
+
+impl ShaderProgramBuilder {
+    pub fn vertex(mut self, source: &str) -> Self {
+        self.vertex = Some(source.to_string());
+        self
+    }
+
+    pub fn fragment(mut self, source: &str) -> Self {
+        self.fragment = Some(source.to_string());
+        self
+    }
+
+    pub fn geometry(mut self, source: &str) -> Self {
+        self.geometry = Some(source.to_string());
+        self
+    }
+
+    pub fn build(&self, context: &GlContext) -> Result<ShaderProgram> {
+        let vertex_source = self.vertex.as_deref()
+            .ok_or_else(|| GearError::InvalidArgument("shader program needs a vertex stage".into()))?;
+        let fragment_source = self.fragment.as_deref()
+            .ok_or_else(|| GearError::InvalidArgument("shader program needs a fragment stage".into()))?;
+
+        let mut stages = vec![(ShaderStage::Vertex, vertex_source), (ShaderStage::Fragment, fragment_source)];
+        if let Some(geometry_source) = self.geometry.as_deref() {
+            stages.push((ShaderStage::Geometry, geometry_source));
+        }
+
+        let gl = context.gl()?;
+        let mut shaders = Vec::with_capacity(stages.len());
+        for (stage, source) in stages {
+            match compile(gl, stage, source) {
+                Ok(shader) => shaders.push(shader),
+                Err(error) => {
+                    for shader in shaders {
+                        let _ = unsafe { gl.DeleteShader(shader) };
+                    }
+                    return Err(error);
+                }
+            }
+        }
+
+        let result = unsafe { link(gl, &shaders) };
+
+        for shader in shaders {
+            let _ = unsafe { gl.DeleteShader(shader) };
+        }
+
+        Ok(ShaderProgram {
+            context: context.clone(),
+            name: result?,
+            locations: RefCell::new(HashMap::new()),
+        })
+    }
+}
+
+/// A linked program, built with `ShaderProgram::builder`. Uniform locations are looked up once
+/// and cached.
+pub struct ShaderProgram {
+    context: GlContext,
+    name: GLuint,
+    locations: RefCell<HashMap<String, GLint>>,
+}
+
+impl ShaderProgram {
+    pub fn builder() -> ShaderProgramBuilder {
+        ShaderProgramBuilder::default()
+    }
+
+    pub fn context(&self) -> &GlContext {
+        &self.context
+    }
+
+    pub fn name(&self) -> GLuint {
+        self.name
+    }
+
+    pub fn use_program(&self) -> Result<()> {
+        unsafe { self.context.gl()?.UseProgram(self.name) }
+    }
+
+    pub fn uniform_location(&self, name: &str) -> Result<GLint> {
+        if let Some(location) = self.locations.borrow().get(name) {
+            return Ok(*location);
+        }
+
+        let cname = CString::new(name)
+            .map_err(|_| GearError::InvalidArgument(format!("uniform name {:?} contains a nul byte", name)))?;
+        let location = unsafe { self.context.gl()?.GetUniformLocation(self.name, cname.as_ptr())? };
+
+        if location < 0 {
+            log::warn!("uniform {} is not active in shader program {}", name, self.name);
+        }
+
+        self.locations.borrow_mut().insert(name.to_string(), location);
+        Ok(location)
+    }
+
+    pub fn set_uniform<U: Uniform>(&self, name: &str, value: U) -> Result<()> {
+        let location = self.uniform_location(name)?;
+        if location < 0 {
+            return Ok(());
+        }
+
+        self.use_program()?;
+        value.set(self.context.gl()?, location)
+    }
+}
+
+impl Drop for ShaderProgram {
+    fn drop(&mut self) {
+        let _ = self.context.with_current(false, |gl| unsafe { gl.DeleteProgram(self.name) });
+    }
+}
+
+fn compile(gl: &GLFunctions, stage: ShaderStage, source: &str) -> Result<GLuint> {
+    let source = CString::new(source)
+        .map_err(|_| GearError::InvalidArgument(format!("{} shader source contains a nul byte", stage)))?;
+
+    unsafe {
+        let shader = gl.CreateShader(stage.gl().unwrap())?;
+        gl.ShaderSource(shader, 1, &source.as_ptr(), null())?;
+        gl.CompileShader(shader)?;
+
+        let mut status = 0;
+        gl.GetShaderiv(shader, gl::COMPILE_STATUS, &mut status)?;
+        if status == 0 {
+            let mut length = 0;
+            gl.GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut length)?;
+            let mut log = vec![0u8; length.max(1) as usize];
+            gl.GetShaderInfoLog(shader, length, null_mut(), log.as_mut_ptr() as *mut GLchar)?;
+            gl.DeleteShader(shader)?;
+
+            return Err(GearError::ShaderCompile { stage, log: info_log(log) });
+        }
+
+        Ok(shader)
+    }
+}
+
+unsafe fn link(gl: &GLFunctions, shaders: &[GLuint]) -> Result<GLuint> {
+    let program = gl.CreateProgram()?;
+
+    for shader in shaders {
+        gl.AttachShader(program, *shader)?;
+    }
+    gl.LinkProgram(program)?;
+    for shader in shaders {
+        gl.DetachShader(program, *shader)?;
+    }
+
+    let mut status = 0;
+    gl.GetProgramiv(program, gl::LINK_STATUS, &mut status)?;
+    if status == 0 {
+        let mut length = 0;
+        gl.GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut length)?;
+        let mut log = vec![0u8; length.max(1) as usize];
+        gl.GetProgramInfoLog(program, length, null_mut(), log.as_mut_ptr() as *mut GLchar)?;
+        gl.DeleteProgram(program)?;
+
+        return Err(GearError::ShaderCompile { stage: ShaderStage::Link, log: info_log(log) });
+    }
+
+    Ok(program)
+}
+
+fn info_log(mut log: Vec<u8>) -> String {
+    if let Some(end) = log.iter().position(|byte| *byte == 0) {
+        log.truncate(end);
+    }
+    String::from_utf8_lossy(&log).trim_end().to_string()
+}
+
 pub const vertex: &str = r"
 #version 330 core
 layout (location = 0) in vec3 aPos;
@@ -5,4 +219,15 @@ layout (location = 0) in vec3 aPos;
 void main()
 {
     gl_Position = vec4(aPos.x, aPos.y, aPos.z, 1.0);
-}";
\ No newline at end of file
+}";
+
+pub const fragment: &str = r"
+#version 330 core
+out vec4 FragColor;
+
+uniform vec4 color;
+
+void main()
+{
+    FragColor = color;
+}";
@@ -0,0 +1,377 @@
+use std::{ffi::c_void, ptr::null};
+
+use bytemuck::Pod;
+
+use crate::{context::GlContext, error::*, gl::{self, types::*, GLFunctions}, uniform::Sampler};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    R8,
+    Rgb8,
+    Rgba8,
+    R32F,
+    Rgb32F,
+    Rgba32F,
+    Depth24,
+    Depth32F,
+    Depth24Stencil8,
+}
+
+impl PixelFormat {
+    pub fn internal_format(self) -> GLenum {
+        match self {
+            PixelFormat::R8 => gl::R8,
+            PixelFormat::Rgb8 => gl::RGB8,
+            PixelFormat::Rgba8 => gl::RGBA8,
+            PixelFormat::R32F => gl::R32F,
+            PixelFormat::Rgb32F => gl::RGB32F,
+            PixelFormat::Rgba32F => gl::RGBA32F,
+            PixelFormat::Depth24 => gl::DEPTH_COMPONENT24,
+            PixelFormat::Depth32F => gl::DEPTH_COMPONENT32F,
+            PixelFormat::Depth24Stencil8 => gl::DEPTH24_STENCIL8,
+        }
+    }
+
+    pub fn format(self) -> GLenum {
+        match self {
+            PixelFormat::R8 | PixelFormat::R32F => gl::RED,
+            PixelFormat::Rgb8 | PixelFormat::Rgb32F => gl::RGB,
+            PixelFormat::Rgba8 | PixelFormat::Rgba32F => gl::RGBA,
+            PixelFormat::Depth24 | PixelFormat::Depth32F => gl::DEPTH_COMPONENT,
+            PixelFormat::Depth24Stencil8 => gl::DEPTH_STENCIL,
+        }
+    }
+
+    pub fn data_type(self) -> GLenum {
+        match self {
+            PixelFormat::R8 | PixelFormat::Rgb8 | PixelFormat::Rgba8 => gl::UNSIGNED_BYTE,
+            PixelFormat::R32F | PixelFormat::Rgb32F | PixelFormat::Rgba32F | PixelFormat::Depth32F => gl::FLOAT,
+            PixelFormat::Depth24 => gl::UNSIGNED_INT,
+            PixelFormat::Depth24Stencil8 => gl::UNSIGNED_INT_24_8,
+        }
+    }
+
+    pub fn channels(self) -> usize {
+        match self {
+            PixelFormat::R8 | PixelFormat::R32F => 1,
+            PixelFormat::Rgb8 | PixelFormat::Rgb32F => 3,
+            PixelFormat::Rgba8 | PixelFormat::Rgba32F => 4,
+            PixelFormat::Depth24 | PixelFormat::Depth32F => 1,
+            PixelFormat::Depth24Stencil8 => 2,
+        }
+    }
+
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::R8 | PixelFormat::Rgb8 | PixelFormat::Rgba8 => self.channels(),
+            PixelFormat::R32F | PixelFormat::Rgb32F | PixelFormat::Rgba32F => self.channels() * 4,
+            PixelFormat::Depth24 | PixelFormat::Depth32F | PixelFormat::Depth24Stencil8 => 4,
+        }
+    }
+
+    pub fn is_depth(self) -> bool {
+        matches!(self, PixelFormat::Depth24 | PixelFormat::Depth32F | PixelFormat::Depth24Stencil8)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinFilter {
+    Nearest,
+    Linear,
+    NearestMipmapNearest,
+    LinearMipmapNearest,
+    NearestMipmapLinear,
+    LinearMipmapLinear,
+}
+
+impl MinFilter {
+    pub fn gl(self) -> GLenum {
+        match self {
+            MinFilter::Nearest => gl::NEAREST,
+            MinFilter::Linear => gl::LINEAR,
+            MinFilter::NearestMipmapNearest => gl::NEAREST_MIPMAP_NEAREST,
+            MinFilter::LinearMipmapNearest => gl::LINEAR_MIPMAP_NEAREST,
+            MinFilter::NearestMipmapLinear => gl::NEAREST_MIPMAP_LINEAR,
+            MinFilter::LinearMipmapLinear => gl::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+
+    fn uses_mipmaps(self) -> bool {
+        !matches!(self, MinFilter::Nearest | MinFilter::Linear)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MagFilter {
+    Nearest,
+    Linear,
+}
+
+impl MagFilter {
+    pub fn gl(self) -> GLenum {
+        match self {
+            MagFilter::Nearest => gl::NEAREST,
+            MagFilter::Linear => gl::LINEAR,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    ClampToBorder,
+}
+
+impl WrapMode {
+    pub fn gl(self) -> GLenum {
+        match self {
+            WrapMode::Repeat => gl::REPEAT,
+            WrapMode::MirroredRepeat => gl::MIRRORED_REPEAT,
+            WrapMode::ClampToEdge => gl::CLAMP_TO_EDGE,
+            WrapMode::ClampToBorder => gl::CLAMP_TO_BORDER,
+        }
+    }
+}
+
+/// A 2D texture of one `PixelFormat`.
+pub struct Texture2D {
+    context: GlContext,
+    name: GLuint,
+    width: GLsizei,
+    height: GLsizei,
+    format: PixelFormat,
+    has_mipmaps: bool,
+}
+
+impl Texture2D {
+    /// Allocates storage without initializing it, e.g. for render targets.
+    pub fn new(context: &GlContext, width: GLsizei, height: GLsizei, format: PixelFormat) -> Result<Self> {
+        if width <= 0 || height <= 0 {
+            return Err(GearError::InvalidArgument("texture size must be greater than 0".into()));
+        }
+
+        let gl = context.gl()?;
+        let mut name: GLuint = 0;
+        unsafe { gl.GenTextures(1, &mut name)? };
+
+        let texture = Texture2D {
+            context: context.clone(),
+            name,
+            width,
+            height,
+            format,
+            has_mipmaps: false,
+        };
+
+        unsafe {
+            bind_for_setup(gl, name)?;
+            image_2d(gl, width, height, format, null())?;
+            // The default minification filter expects mipmaps, which would leave the texture incomplete.
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint)?;
+        }
+
+        Ok(texture)
+    }
+
+    pub fn from_pixels<T: Pod>(
+        context: &GlContext,
+        width: GLsizei,
+        height: GLsizei,
+        format: PixelFormat,
+        pixels: &[T],
+    ) -> Result<Self> {
+        let mut texture = Texture2D::new(context, width, height, format)?;
+        texture.upload(pixels)?;
+        Ok(texture)
+    }
+
+    pub fn context(&self) -> &GlContext {
+        &self.context
+    }
+
+    pub fn name(&self) -> GLuint {
+        self.name
+    }
+
+    pub fn width(&self) -> GLsizei {
+        self.width
+    }
+
+    pub fn height(&self) -> GLsizei {
+        self.height
+    }
+
+    pub fn size(&self) -> (GLsizei, GLsizei) {
+        (self.width, self.height)
+    }
+
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Replaces the whole image. Mipmaps have to be generated again afterwards.
+    pub fn upload<T: Pod>(&mut self, pixels: &[T]) -> Result<()> {
+        let bytes: &[u8] = bytemuck::cast_slice(pixels);
+        check_size(bytes, self.width, self.height, self.format)?;
+
+        let gl = self.context.gl()?;
+        unsafe {
+            bind_for_setup(gl, self.name)?;
+            image_2d(gl, self.width, self.height, self.format, bytes.as_ptr() as *const c_void)
+        }
+    }
+
+    pub fn sub_image<T: Pod>(&mut self, x: GLint, y: GLint, width: GLsizei, height: GLsizei, pixels: &[T]) -> Result<()> {
+        let fits = |start: GLint, len: GLsizei, size: GLsizei| {
+            start >= 0 && len >= 0 && start.checked_add(len).is_some_and(|end| end <= size)
+        };
+        if !fits(x, width, self.width) || !fits(y, height, self.height) {
+            return Err(GearError::InvalidArgument(format!(
+                "sub image {}x{} at ({}, {}) is out of bounds for a {}x{} texture",
+                width, height, x, y, self.width, self.height
+            )));
+        }
+
+        let bytes: &[u8] = bytemuck::cast_slice(pixels);
+        check_size(bytes, width, height, self.format)?;
+
+        let gl = self.context.gl()?;
+        unsafe {
+            bind_for_setup(gl, self.name)?;
+            gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1)?;
+            gl.TexSubImage2D(
+                gl::TEXTURE_2D,
+                0,
+                x,
+                y,
+                width,
+                height,
+                self.format.format(),
+                self.format.data_type(),
+                bytes.as_ptr() as *const c_void,
+            )
+        }
+    }
+
+    pub fn generate_mipmaps(&mut self) -> Result<()> {
+        let gl = self.context.gl()?;
+        unsafe {
+            bind_for_setup(gl, self.name)?;
+            gl.GenerateMipmap(gl::TEXTURE_2D)?;
+        }
+
+        self.has_mipmaps = true;
+        Ok(())
+    }
+
+    pub fn set_min_filter(&mut self, filter: MinFilter) -> Result<()> {
+        if filter.uses_mipmaps() && !self.has_mipmaps {
+            return Err(GearError::InvalidArgument("mipmap filtering needs generate_mipmaps to be called first".into()));
+        }
+
+        self.parameter(gl::TEXTURE_MIN_FILTER, filter.gl() as GLint)
+    }
+
+    pub fn set_mag_filter(&mut self, filter: MagFilter) -> Result<()> {
+        self.parameter(gl::TEXTURE_MAG_FILTER, filter.gl() as GLint)
+    }
+
+    pub fn set_wrap(&mut self, s: WrapMode, t: WrapMode) -> Result<()> {
+        self.parameter(gl::TEXTURE_WRAP_S, s.gl() as GLint)?;
+        self.parameter(gl::TEXTURE_WRAP_T, t.gl() as GLint)
+    }
+
+    /// Only used with `WrapMode::ClampToBorder`.
+    pub fn set_border_color(&mut self, color: [f32; 4]) -> Result<()> {
+        let gl = self.context.gl()?;
+        unsafe {
+            bind_for_setup(gl, self.name)?;
+            gl.TexParameterfv(gl::TEXTURE_2D, gl::TEXTURE_BORDER_COLOR, color.as_ptr())
+        }
+    }
+
+    /// Binds the texture to `unit`; the returned sampler goes straight into `set_uniform`.
+    pub fn bind(&self, unit: u32) -> Result<Sampler> {
+        check_unit(unit)?;
+        let gl = self.context.gl()?;
+        unsafe {
+            gl.ActiveTexture(gl::TEXTURE0 + unit)?;
+            gl.BindTexture(gl::TEXTURE_2D, self.name)?;
+        }
+
+        Ok(Sampler(unit))
+    }
+
+    pub fn unbind(&self, unit: u32) -> Result<()> {
+        check_unit(unit)?;
+        let gl = self.context.gl()?;
+        unsafe {
+            gl.ActiveTexture(gl::TEXTURE0 + unit)?;
+            gl.BindTexture(gl::TEXTURE_2D, 0)
+        }
+    }
+
+    fn parameter(&self, parameter: GLenum, value: GLint) -> Result<()> {
+        let gl = self.context.gl()?;
+        unsafe {
+            bind_for_setup(gl, self.name)?;
+            gl.TexParameteri(gl::TEXTURE_2D, parameter, value)
+        }
+    }
+}
+
+impl Drop for Texture2D {
+    fn drop(&mut self) {
+        let _ = self.context.with_current(false, |gl| unsafe { gl.DeleteTextures(1, &self.name) });
+    }
+}
+
+/// The last unit GL 3.3 guarantees. Textures are bound here while they are set up, so uploads and
+/// parameter changes never replace what `bind` put on a unit for drawing.
+pub const SETUP_TEXTURE_UNIT: u32 = 47;
+
+unsafe fn bind_for_setup(gl: &GLFunctions, name: GLuint) -> Result<()> {
+    gl.ActiveTexture(gl::TEXTURE0 + SETUP_TEXTURE_UNIT)?;
+    gl.BindTexture(gl::TEXTURE_2D, name)
+}
+
+fn check_unit(unit: u32) -> Result<()> {
+    if unit >= SETUP_TEXTURE_UNIT {
+        return Err(GearError::InvalidArgument(format!(
+            "texture unit {} is out of range; units from {} on are reserved",
+            unit, SETUP_TEXTURE_UNIT
+        )));
+    }
+
+    Ok(())
+}
+
+fn check_size(bytes: &[u8], width: GLsizei, height: GLsizei, format: PixelFormat) -> Result<()> {
+    let expected = width as usize * height as usize * format.bytes_per_pixel();
+    if bytes.len() != expected {
+        return Err(GearError::InvalidArgument(format!(
+            "{}x{} {:?} pixels need {} bytes, got {}",
+            width, height, format, expected, bytes.len()
+        )));
+    }
+
+    Ok(())
+}
+
+// Rows of R8 and RGB8 images are not 4-byte aligned, which is what GL assumes by default.
+unsafe fn image_2d(gl: &GLFunctions, width: GLsizei, height: GLsizei, format: PixelFormat, pixels: *const c_void) -> Result<()> {
+    gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1)?;
+    gl.TexImage2D(
+        gl::TEXTURE_2D,
+        0,
+        format.internal_format() as GLint,
+        width,
+        height,
+        0,
+        format.format(),
+        format.data_type(),
+        pixels,
+    )
+}
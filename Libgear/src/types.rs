@@ -5,26 +5,52 @@ pub const GLFWRelease: i32 = 0i32;
 pub const GLFWPress: i32 = 1i32;
 pub const GLFWRepeat: i32 = 2i32;
 
-pub const GLFWKeyEscape: i32 = 256;
-
-pub const True: i32 = 0;
-pub const False: i32 = 1;
-
-pub const glColorBufferBit: u32 = 16384;
-pub const glStaticDraw: u32 = 0x88E4;
-pub const glVertexShader: GLenum = 0x8B31;
-
-pub const glArrayBuffer: GLenum = 0x8892;
-
-pub type GLsizeiptr = isize;
-pub type GLchar = c_char;
-pub type GLenum = c_uint;
-pub type GLint = c_int;
-pub type GLuint = c_uint;
-pub type GLsizei = c_int;
-pub type GLcampf = f32;
-pub type GLbitfield = c_uint;
-pub type GLFWframebuffersizefun = extern "C" fn(*mut GLFWwindow, GLint, GLint);
+pub const True: i32 = 1;
+pub const False: i32 = 0;
+
+pub const GLFWResizable: c_int = 0x00020003;
+pub const GLFWVisible: c_int = 0x00020004;
+pub const GLFWDecorated: c_int = 0x00020005;
+pub const GLFWSamples: c_int = 0x0002100D;
+pub const GLFWContextVersionMajor: c_int = 0x00022002;
+pub const GLFWContextVersionMinor: c_int = 0x00022003;
+pub const GLFWOpenGLForwardCompat: c_int = 0x00022006;
+pub const GLFWOpenGLDebugContext: c_int = 0x00022007;
+pub const GLFWOpenGLProfile: c_int = 0x00022008;
+pub const GLFWContextCreationApi: c_int = 0x0002200B;
+
+pub const GLFWCursor: c_int = 0x00033001;
+pub const GLFWRawMouseMotion: c_int = 0x00033005;
+
+pub const GLFWCursorNormal: c_int = 0x00034001;
+pub const GLFWCursorHidden: c_int = 0x00034002;
+pub const GLFWCursorDisabled: c_int = 0x00034003;
+
+pub const GLFWConnected: c_int = 0x00040001;
+pub const GLFWDisconnected: c_int = 0x00040002;
+
+pub const GLFWOpenGLAnyProfile: c_int = 0;
+pub const GLFWOpenGLCoreProfile: c_int = 0x00032001;
+pub const GLFWOpenGLCompatProfile: c_int = 0x00032002;
+
+pub const GLFWNativeContextApi: c_int = 0x00036001;
+pub const GLFWEglContextApi: c_int = 0x00036002;
+pub const GLFWOSMesaContextApi: c_int = 0x00036003;
+
+pub type GLFWframebuffersizefun = extern "C" fn(*mut GLFWwindow, c_int, c_int);
+pub type GLFWkeyfun = extern "C" fn(*mut GLFWwindow, c_int, c_int, c_int, c_int);
+pub type GLFWcharfun = extern "C" fn(*mut GLFWwindow, c_uint);
+pub type GLFWcursorposfun = extern "C" fn(*mut GLFWwindow, c_double, c_double);
+pub type GLFWcursorenterfun = extern "C" fn(*mut GLFWwindow, c_int);
+pub type GLFWmousebuttonfun = extern "C" fn(*mut GLFWwindow, c_int, c_int, c_int);
+pub type GLFWscrollfun = extern "C" fn(*mut GLFWwindow, c_double, c_double);
+pub type GLFWjoystickfun = extern "C" fn(c_int, c_int);
+pub type GLFWwindowsizefun = extern "C" fn(*mut GLFWwindow, c_int, c_int);
+pub type GLFWwindowfocusfun = extern "C" fn(*mut GLFWwindow, c_int);
+pub type GLFWwindowiconifyfun = extern "C" fn(*mut GLFWwindow, c_int);
+pub type GLFWwindowclosefun = extern "C" fn(*mut GLFWwindow);
+pub type GLFWdropfun = extern "C" fn(*mut GLFWwindow, c_int, *mut *const c_char);
+pub type GLFWwindowcontentscalefun = extern "C" fn(*mut GLFWwindow, c_float, c_float);
 pub type GLFWglproc = *const c_void;
 
 #[allow(missing_copy_implementations)]
@@ -33,4 +59,16 @@ pub enum GLFWwindow {}
 
 #[allow(missing_copy_implementations)]
 #[derive(Debug)]
-pub enum GLFWmonitor {}
\ No newline at end of file
+pub enum GLFWmonitor {}
+
+#[allow(missing_copy_implementations)]
+#[derive(Debug)]
+pub enum GLFWcursor {}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct GLFWimage {
+    pub width: c_int,
+    pub height: c_int,
+    pub pixels: *mut c_uchar,
+}
@@ -0,0 +1,88 @@
+use crate::{error::*, gl::{self, types::*, GLFunctions}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sampler(pub u32);
+
+/// A value that `ShaderProgram::set_uniform` can set.
+pub trait Uniform {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()>;
+}
+
+impl<T: Uniform + ?Sized> Uniform for &T {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        (**self).set(gl, location)
+    }
+}
+
+impl Uniform for f32 {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform1f(location, *self) }
+    }
+}
+
+impl Uniform for i32 {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform1i(location, *self) }
+    }
+}
+
+impl Uniform for bool {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform1i(location, *self as GLint) }
+    }
+}
+
+impl Uniform for [f32; 2] {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform2fv(location, 1, self.as_ptr()) }
+    }
+}
+
+impl Uniform for [f32; 3] {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform3fv(location, 1, self.as_ptr()) }
+    }
+}
+
+impl Uniform for [f32; 4] {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform4fv(location, 1, self.as_ptr()) }
+    }
+}
+
+impl Uniform for [i32; 2] {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform2iv(location, 1, self.as_ptr()) }
+    }
+}
+
+impl Uniform for [i32; 3] {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform3iv(location, 1, self.as_ptr()) }
+    }
+}
+
+impl Uniform for [i32; 4] {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform4iv(location, 1, self.as_ptr()) }
+    }
+}
+
+/// Matrices are column major, matching GLSL.
+impl Uniform for [[f32; 3]; 3] {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.UniformMatrix3fv(location, 1, gl::FALSE, self.as_ptr() as *const GLfloat) }
+    }
+}
+
+impl Uniform for [[f32; 4]; 4] {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.UniformMatrix4fv(location, 1, gl::FALSE, self.as_ptr() as *const GLfloat) }
+    }
+}
+
+impl Uniform for Sampler {
+    fn set(&self, gl: &GLFunctions, location: GLint) -> Result<()> {
+        unsafe { gl.Uniform1i(location, self.0 as GLint) }
+    }
+}
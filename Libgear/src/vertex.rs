@@ -0,0 +1,336 @@
+use std::ffi::c_void;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::{buffer::*, context::GlContext, error::*, gl, gl::types::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttribType {
+    Float,
+    Byte,
+    UnsignedByte,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+}
+
+impl AttribType {
+    pub fn gl(self) -> GLenum {
+        match self {
+            AttribType::Float => gl::FLOAT,
+            AttribType::Byte => gl::BYTE,
+            AttribType::UnsignedByte => gl::UNSIGNED_BYTE,
+            AttribType::Short => gl::SHORT,
+            AttribType::UnsignedShort => gl::UNSIGNED_SHORT,
+            AttribType::Int => gl::INT,
+            AttribType::UnsignedInt => gl::UNSIGNED_INT,
+        }
+    }
+
+    pub fn size(self) -> usize {
+        match self {
+            AttribType::Byte | AttribType::UnsignedByte => 1,
+            AttribType::Short | AttribType::UnsignedShort => 2,
+            AttribType::Float | AttribType::Int | AttribType::UnsignedInt => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VertexAttribute {
+    pub location: GLuint,
+    pub components: GLint,
+    pub ty: AttribType,
+    pub normalized: bool,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VertexLayout {
+    attributes: Vec<VertexAttribute>,
+    stride: usize,
+    divisor: GLuint,
+}
+
+impl VertexLayout {
+    pub fn new() -> Self {
+        VertexLayout::default()
+    }
+
+    /// Appends a tightly packed attribute after the previous one.
+    pub fn attribute(mut self, location: GLuint, components: GLint, ty: AttribType, normalized: bool) -> Self {
+        let offset = self.stride;
+        self.stride += components as usize * ty.size();
+        self.attributes.push(VertexAttribute { location, components, ty, normalized, offset });
+        self
+    }
+
+    pub fn field(mut self, location: GLuint, offset: usize, format: (AttribType, GLint, bool)) -> Self {
+        let (ty, components, normalized) = format;
+        self.stride = self.stride.max(offset + components as usize * ty.size());
+        self.attributes.push(VertexAttribute { location, components, ty, normalized, offset });
+        self
+    }
+
+    pub fn stride(mut self, stride: usize) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    /// Advances the attributes once per `divisor` instances instead of once per vertex.
+    pub fn divisor(mut self, divisor: GLuint) -> Self {
+        self.divisor = divisor;
+        self
+    }
+
+    pub fn attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+
+    pub fn stride_bytes(&self) -> usize {
+        self.stride
+    }
+
+    pub fn instance_divisor(&self) -> GLuint {
+        self.divisor
+    }
+}
+
+/// The attribute type of a vertex field.
+pub trait VertexFormat {
+    const TYPE: AttribType;
+    const COMPONENTS: GLint;
+    const NORMALIZED: bool = false;
+}
+
+macro_rules! vertex_format {
+    ($ty:ty, $attrib:ident) => {
+        impl VertexFormat for $ty {
+            const TYPE: AttribType = AttribType::$attrib;
+            const COMPONENTS: GLint = 1;
+        }
+
+        impl VertexFormat for [$ty; 2] {
+            const TYPE: AttribType = AttribType::$attrib;
+            const COMPONENTS: GLint = 2;
+        }
+
+        impl VertexFormat for [$ty; 3] {
+            const TYPE: AttribType = AttribType::$attrib;
+            const COMPONENTS: GLint = 3;
+        }
+
+        impl VertexFormat for [$ty; 4] {
+            const TYPE: AttribType = AttribType::$attrib;
+            const COMPONENTS: GLint = 4;
+        }
+    };
+}
+
+vertex_format!(f32, Float);
+vertex_format!(i8, Byte);
+vertex_format!(u8, UnsignedByte);
+vertex_format!(i16, Short);
+vertex_format!(u16, UnsignedShort);
+vertex_format!(i32, Int);
+vertex_format!(u32, UnsignedInt);
+
+/// Integer data that the shader should see as floats in [0, 1] (or [-1, 1] when signed).
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Normalized<T>(pub T);
+
+unsafe impl<T: Zeroable> Zeroable for Normalized<T> {}
+unsafe impl<T: Pod> Pod for Normalized<T> {}
+
+impl<T: VertexFormat> VertexFormat for Normalized<T> {
+    const TYPE: AttribType = T::TYPE;
+    const COMPONENTS: GLint = T::COMPONENTS;
+    const NORMALIZED: bool = true;
+}
+
+/// The attribute format of the field `field` returns; used by `impl_vertex!`.
+pub fn format_of<V, F: VertexFormat, G: Fn(&V) -> &F>(_field: G) -> (AttribType, GLint, bool) {
+    (F::TYPE, F::COMPONENTS, F::NORMALIZED)
+}
+
+/// A vertex struct whose layout is known, usually implemented with `impl_vertex!`.
+pub trait Vertex: Pod {
+    fn layout() -> VertexLayout;
+}
+
+/// Implements `Vertex` for a `#[repr(C)]` struct, mapping each field to an attribute location:
+/// `impl_vertex!(MyVertex { position => 0, color => 1 })`.
+#[macro_export]
+macro_rules! impl_vertex {
+    ($ty:ty { $($field:ident => $location:expr),* $(,)? }) => {
+        impl $crate::vertex::Vertex for $ty {
+            fn layout() -> $crate::vertex::VertexLayout {
+                $crate::vertex::VertexLayout::new()
+                    $(.field(
+                        $location,
+                        ::std::mem::offset_of!($ty, $field),
+                        $crate::vertex::format_of(|this: &$ty| &this.$field),
+                    ))*
+                    .stride(::std::mem::size_of::<$ty>())
+            }
+        }
+    };
+}
+
+/// An integer type that can be used for indices.
+pub trait Index: Pod {
+    const TYPE: GLenum;
+}
+
+impl Index for u8 {
+    const TYPE: GLenum = gl::UNSIGNED_BYTE;
+}
+
+impl Index for u16 {
+    const TYPE: GLenum = gl::UNSIGNED_SHORT;
+}
+
+impl Index for u32 {
+    const TYPE: GLenum = gl::UNSIGNED_INT;
+}
+
+/// Which buffers the vertex attributes and indices come from. Its vertex and index counts are
+/// read from the attached buffers' current sizes, so uploading new data to a buffer does not need
+/// it to be attached again.
+pub struct VertexArray {
+    context: GlContext,
+    name: GLuint,
+    vertex_sources: Vec<VertexSource>,
+    index_type: Option<GLenum>,
+    indices: Option<BufferSize>,
+}
+
+// A per-vertex buffer and the attribute locations still reading from it.
+struct VertexSource {
+    locations: Vec<GLuint>,
+    size: BufferSize,
+    stride: usize,
+}
+
+impl VertexArray {
+    pub fn new(context: &GlContext) -> Result<Self> {
+        let mut name: GLuint = 0;
+        unsafe { context.gl_unshared()?.GenVertexArrays(1, &mut name)? };
+
+        Ok(VertexArray {
+            context: context.clone(),
+            name,
+            vertex_sources: Vec::new(),
+            index_type: None,
+            indices: None,
+        })
+    }
+
+    pub fn context(&self) -> &GlContext {
+        &self.context
+    }
+
+    pub fn name(&self) -> GLuint {
+        self.name
+    }
+
+    /// How many whole vertices every per-vertex buffer attached holds. Instanced attributes do not
+    /// limit it.
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_sources.iter().map(|source| source.size.bytes() / source.stride).min().unwrap_or(0)
+    }
+
+    pub fn index_type(&self) -> Option<GLenum> {
+        self.index_type
+    }
+
+    pub fn index_count(&self) -> usize {
+        match (&self.indices, self.index_type) {
+            (Some(size), Some(index_type)) => size.bytes() / index_size(index_type),
+            _ => 0,
+        }
+    }
+
+    pub fn bind(&self) -> Result<()> {
+        unsafe { self.context.gl_unshared()?.BindVertexArray(self.name) }
+    }
+
+    pub fn unbind(&self) -> Result<()> {
+        unsafe { self.context.gl_unshared()?.BindVertexArray(0) }
+    }
+
+    pub fn bind_buffer<T: Pod>(&mut self, buffer: &Buffer<T>, layout: &VertexLayout) -> Result<()> {
+        if buffer.target() != BufferTarget::ArrayBuffer {
+            return Err(GearError::InvalidArgument("vertex attributes need an array buffer".into()));
+        }
+
+        let gl = self.context.gl_unshared()?;
+        self.bind()?;
+        buffer.bind()?;
+
+        for attribute in layout.attributes() {
+            let offset = attribute.offset as *const c_void;
+            let stride = layout.stride_bytes() as GLsizei;
+
+            unsafe {
+                if attribute.ty != AttribType::Float && !attribute.normalized {
+                    gl.VertexAttribIPointer(attribute.location, attribute.components, attribute.ty.gl(), stride, offset)?;
+                } else {
+                    let normalized = if attribute.normalized { gl::TRUE } else { gl::FALSE };
+                    gl.VertexAttribPointer(attribute.location, attribute.components, attribute.ty.gl(), normalized, stride, offset)?;
+                }
+                gl.EnableVertexAttribArray(attribute.location)?;
+                gl.VertexAttribDivisor(attribute.location, layout.instance_divisor())?;
+            }
+        }
+
+        let locations: Vec<GLuint> = layout.attributes().iter().map(|attribute| attribute.location).collect();
+        for source in &mut self.vertex_sources {
+            source.locations.retain(|location| !locations.contains(location));
+        }
+        self.vertex_sources.retain(|source| !source.locations.is_empty());
+        if layout.stride_bytes() > 0 && layout.instance_divisor() == 0 && !locations.is_empty() {
+            self.vertex_sources.push(VertexSource { locations, size: buffer.size(), stride: layout.stride_bytes() });
+        }
+        self.unbind()
+    }
+
+    pub fn bind_vertices<V: Vertex>(&mut self, buffer: &Buffer<V>) -> Result<()> {
+        self.bind_buffer(buffer, &V::layout())
+    }
+
+    pub fn bind_index_buffer<I: Index>(&mut self, buffer: &Buffer<I>) -> Result<()> {
+        self.attach_indices(buffer, I::TYPE)
+    }
+
+    // Indices of `index_type` in a buffer of any element type, for the untyped buffers of the C API.
+    pub(crate) fn attach_indices<T: Pod>(&mut self, buffer: &Buffer<T>, index_type: GLenum) -> Result<()> {
+        if buffer.target() != BufferTarget::ElementArrayBuffer {
+            return Err(GearError::InvalidArgument("indices need an element array buffer".into()));
+        }
+
+        self.bind()?;
+        buffer.bind()?;
+
+        self.index_type = Some(index_type);
+        self.indices = Some(buffer.size());
+        // Left bound, the next element array buffer created anywhere would replace these indices.
+        self.unbind()
+    }
+}
+
+impl Drop for VertexArray {
+    fn drop(&mut self) {
+        let _ = self.context.with_current(true, |gl| unsafe { gl.DeleteVertexArrays(1, &self.name) });
+    }
+}
+
+pub(crate) fn index_size(index_type: GLenum) -> usize {
+    match index_type {
+        gl::UNSIGNED_BYTE => 1,
+        gl::UNSIGNED_SHORT => 2,
+        _ => 4,
+    }
+}
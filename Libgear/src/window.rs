@@ -0,0 +1,635 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{vec_deque, VecDeque},
+    ffi::*,
+    path::PathBuf,
+    ptr::null_mut,
+    slice,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{context::GlContext, error::*, event::*, gl, image::*, input::*, types::*};
+use super::*;
+
+// Windows and cursors keep GLFW initialized; the last of them to go terminates it, which would
+// free cursors still in use.
+static GLFW_USERS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    // Windows created by `Window::new`, the only ones whose user pointer is a `WindowState`.
+    static GEAR_WINDOWS: RefCell<Vec<*mut GLFWwindow>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    Any,
+    Core,
+    Compatibility,
+}
+
+impl Profile {
+    pub(crate) fn hint(self) -> c_int {
+        match self {
+            Profile::Any => GLFWOpenGLAnyProfile,
+            Profile::Core => GLFWOpenGLCoreProfile,
+            Profile::Compatibility => GLFWOpenGLCompatProfile,
+        }
+    }
+
+    pub(crate) fn from_hint(hint: c_int) -> Self {
+        if hint == GLFWOpenGLCoreProfile {
+            Profile::Core
+        } else if hint == GLFWOpenGLCompatProfile {
+            Profile::Compatibility
+        } else {
+            Profile::Any
+        }
+    }
+}
+
+/// EGL and OSMesa let the context come from Mesa (e.g. llvmpipe) instead of the display's driver.
+/// OSMesa needs libOSMesa at runtime; with the `osmesa` feature it is the only API GLFW offers, and
+/// `Native` picks it as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextApi {
+    Native,
+    Egl,
+    OsMesa,
+}
+
+impl ContextApi {
+    pub(crate) fn hint(self) -> c_int {
+        match self {
+            ContextApi::Native => GLFWNativeContextApi,
+            ContextApi::Egl => GLFWEglContextApi,
+            ContextApi::OsMesa => GLFWOSMesaContextApi,
+        }
+    }
+
+    pub(crate) fn from_hint(hint: c_int) -> Self {
+        if hint == GLFWEglContextApi {
+            ContextApi::Egl
+        } else if hint == GLFWOSMesaContextApi {
+            ContextApi::OsMesa
+        } else {
+            ContextApi::Native
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Monitor {
+    Primary,
+    Handle(*mut GLFWmonitor),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowHints {
+    pub context_version: (c_int, c_int),
+    pub profile: Profile,
+    pub context_api: ContextApi,
+    pub samples: c_int,
+    pub visible: bool,
+    pub resizable: bool,
+    pub decorated: bool,
+    pub debug_context: bool,
+}
+
+impl Default for WindowHints {
+    fn default() -> Self {
+        WindowHints {
+            context_version: crate::gl::TABLE_VERSION,
+            profile: if crate::gl::TABLE_COMPATIBILITY { Profile::Compatibility } else { Profile::Core },
+            context_api: ContextApi::Native,
+            samples: 0,
+            visible: true,
+            resizable: true,
+            decorated: true,
+            debug_context: cfg!(feature = "gl-debug"),
+        }
+    }
+}
+
+impl WindowHints {
+    fn apply(&self) {
+        unsafe {
+            glfwDefaultWindowHints();
+            glfwWindowHint(GLFWContextVersionMajor, self.context_version.0);
+            glfwWindowHint(GLFWContextVersionMinor, self.context_version.1);
+            glfwWindowHint(GLFWOpenGLProfile, self.profile.hint());
+            if self.profile == Profile::Core {
+                glfwWindowHint(GLFWOpenGLForwardCompat, True);
+            }
+            glfwWindowHint(GLFWContextCreationApi, self.context_api.hint());
+            glfwWindowHint(GLFWSamples, self.samples);
+            glfwWindowHint(GLFWVisible, glfw_bool(self.visible));
+            glfwWindowHint(GLFWResizable, glfw_bool(self.resizable));
+            glfwWindowHint(GLFWDecorated, glfw_bool(self.decorated));
+            glfwWindowHint(GLFWOpenGLDebugContext, glfw_bool(self.debug_context));
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowConfig {
+    pub width: c_int,
+    pub height: c_int,
+    pub title: CString,
+    pub(crate) monitor: Option<Monitor>,
+    pub(crate) share: *mut GLFWwindow,
+    pub hints: WindowHints,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig {
+            width: 500,
+            height: 500,
+            title: CString::default(),
+            monitor: None,
+            share: null_mut(),
+            hints: WindowHints::default(),
+        }
+    }
+}
+
+impl WindowConfig {
+    pub fn new(title: &str) -> Self {
+        WindowConfig::default().title(title)
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = CString::new(title.replace('\0', "")).unwrap_or_default();
+        self
+    }
+
+    pub fn size(mut self, width: c_int, height: c_int) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn fullscreen(mut self) -> Self {
+        self.monitor = Some(Monitor::Primary);
+        self
+    }
+
+    /// Makes the window fullscreen on `monitor`, or windowed when it is null.
+    ///
+    /// # Safety
+    /// `monitor` has to be null or a monitor GLFW returned and hasn't disconnected since.
+    pub unsafe fn monitor(mut self, monitor: *mut GLFWmonitor) -> Self {
+        self.monitor = if monitor.is_null() { None } else { Some(Monitor::Handle(monitor)) };
+        self
+    }
+
+    /// Shares objects with the context of a raw GLFW window; prefer `share_with`.
+    ///
+    /// # Safety
+    /// `share` has to be null or a live GLFW window. Windows that Gear didn't create can be shared
+    /// with, but the new window's `GlContext` won't count their context as part of its group.
+    pub unsafe fn share(mut self, share: *mut GLFWwindow) -> Self {
+        self.share = share;
+        self
+    }
+
+    /// Shares buffers, textures and programs with the context of `window`.
+    pub fn share_with(self, window: &Window) -> Self {
+        unsafe { self.share(window.handle()) }
+    }
+
+    pub fn context_version(mut self, major: c_int, minor: c_int) -> Self {
+        self.hints.context_version = (major, minor);
+        self
+    }
+
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.hints.profile = profile;
+        self
+    }
+
+    pub fn context_api(mut self, context_api: ContextApi) -> Self {
+        self.hints.context_api = context_api;
+        self
+    }
+
+    pub fn samples(mut self, samples: c_int) -> Self {
+        self.hints.samples = samples;
+        self
+    }
+
+    /// An invisible window whose context renders offscreen. GLFW still needs a display to start
+    /// on Linux, so CI machines without one run under `xvfb-run` or build with the `osmesa` feature.
+    pub fn headless(self) -> Self {
+        self.visible(false).decorated(false)
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.hints.visible = visible;
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.hints.resizable = resizable;
+        self
+    }
+
+    pub fn decorated(mut self, decorated: bool) -> Self {
+        self.hints.decorated = decorated;
+        self
+    }
+
+    pub fn debug_context(mut self, debug_context: bool) -> Self {
+        self.hints.debug_context = debug_context;
+        self
+    }
+
+    // GLFW has to be initialized before this is called.
+    pub(crate) fn create(&self) -> *mut GLFWwindow {
+        self.hints.apply();
+
+        let monitor = match self.monitor {
+            Some(Monitor::Primary) => unsafe { glfwGetPrimaryMonitor() },
+            Some(Monitor::Handle(monitor)) => monitor,
+            None => null_mut(),
+        };
+
+        unsafe { glfwCreateWindow(self.width, self.height, self.title.as_ptr(), monitor, self.share) }
+    }
+}
+
+struct WindowState {
+    context: GlContext,
+    events: RefCell<VecDeque<Event>>,
+    framebuffer_size: Cell<Option<(c_int, c_int)>>,
+}
+
+impl WindowState {
+    // Events raised while the queue is borrowed (a nested poll) are dropped instead of panicking
+    // across the C boundary.
+    fn push(&self, event: Event) {
+        if let Ok(mut events) = self.events.try_borrow_mut() {
+            events.push_back(event);
+        }
+    }
+}
+
+/// A GLFW window and its OpenGL context. GLFW is initialized with the first window and
+/// terminated when the last one (and the last cursor) is dropped.
+pub struct Window {
+    handle: *mut GLFWwindow,
+    state: Box<WindowState>,
+}
+
+impl Window {
+    pub fn new(config: &WindowConfig) -> Result<Window> {
+        if config.width <= 0 || config.height <= 0 {
+            return Err(GearError::InvalidArgument("window size must be greater than 0".into()));
+        }
+
+        unsafe {
+            if glfwInit() == 0 {
+                return Err(GearError::Init);
+            }
+
+            let handle = config.create();
+
+            if handle.is_null() {
+                if GLFW_USERS.load(Ordering::SeqCst) == 0 {
+                    glfwTerminate();
+                }
+                return Err(GearError::WindowCreation);
+            }
+
+            retain_glfw();
+
+            let share = GEAR_WINDOWS.with(|windows| windows.borrow().contains(&config.share))
+                .then(|| window_state(config.share))
+                .flatten()
+                .map(|state| &state.context);
+            let state = Box::new(WindowState {
+                context: GlContext::load(handle, share),
+                events: RefCell::default(),
+                framebuffer_size: Cell::default(),
+            });
+            state.framebuffer_size.set(Some(framebuffer_size(handle)));
+
+            glfwSetWindowUserPointer(handle, &*state as *const WindowState as *mut c_void);
+            glfwSetWindowSizeCallback(handle, Some(window_size_callback));
+            glfwSetFramebufferSizeCallback(handle, Some(framebuffer_size_callback));
+            glfwSetKeyCallback(handle, Some(key_callback));
+            glfwSetCharCallback(handle, Some(char_callback));
+            glfwSetCursorPosCallback(handle, Some(cursor_pos_callback));
+            glfwSetCursorEnterCallback(handle, Some(cursor_enter_callback));
+            glfwSetMouseButtonCallback(handle, Some(mouse_button_callback));
+            glfwSetScrollCallback(handle, Some(scroll_callback));
+            glfwSetWindowFocusCallback(handle, Some(window_focus_callback));
+            glfwSetWindowIconifyCallback(handle, Some(window_iconify_callback));
+            glfwSetWindowCloseCallback(handle, Some(window_close_callback));
+            glfwSetDropCallback(handle, Some(drop_callback));
+            glfwSetWindowContentScaleCallback(handle, Some(content_scale_callback));
+            GEAR_WINDOWS.with(|windows| windows.borrow_mut().push(handle));
+
+            Ok(Window { handle, state })
+        }
+    }
+
+    pub fn handle(&self) -> *mut GLFWwindow {
+        self.handle
+    }
+
+    pub fn context(&self) -> &GlContext {
+        &self.state.context
+    }
+
+    pub fn make_current(&self) {
+        unsafe { glfwMakeContextCurrent(self.handle) }
+    }
+
+    /// With several windows open, usually only one of them should wait for vsync.
+    pub fn set_swap_interval(&self, interval: c_int) {
+        self.make_current();
+        unsafe { glfwSwapInterval(interval) }
+    }
+
+    pub fn framebuffer_size(&self) -> (c_int, c_int) {
+        framebuffer_size(self.handle)
+    }
+
+    /// Reads back the window's framebuffer (the back buffer until `frame_end` swaps it).
+    pub fn read_pixels(&self) -> Result<Image> {
+        let gl = self.context().gl_unshared()?;
+        let (width, height) = self.framebuffer_size();
+        unsafe {
+            gl.BindFramebuffer(gl::READ_FRAMEBUFFER, 0)?;
+            read_pixels(gl, width, height)
+        }
+    }
+
+    pub fn should_close(&self) -> bool {
+        unsafe { glfwWindowShouldClose(self.handle) != False }
+    }
+
+    pub fn set_should_close(&self, value: bool) {
+        unsafe { glfwSetWindowShouldClose(self.handle, glfw_bool(value)) }
+    }
+
+    pub fn poll_events(&self) {
+        unsafe { glfwPollEvents() }
+    }
+
+    pub fn swap_buffers(&self) {
+        unsafe { glfwSwapBuffers(self.handle) }
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        key != Key::Unknown && unsafe { glfwGetKey(self.handle, key.raw()) } == GLFWPress
+    }
+
+    pub fn cursor_position(&self) -> (f64, f64) {
+        let (mut x, mut y) = (0.0, 0.0);
+        unsafe { glfwGetCursorPos(self.handle, &mut x, &mut y) };
+        (x, y)
+    }
+
+    pub fn set_cursor_position(&self, x: f64, y: f64) {
+        unsafe { glfwSetCursorPos(self.handle, x, y) }
+    }
+
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        unsafe { glfwGetMouseButton(self.handle, button.raw()) == GLFWPress }
+    }
+
+    pub fn cursor_mode(&self) -> CursorMode {
+        CursorMode::from_raw(unsafe { glfwGetInputMode(self.handle, GLFWCursor) })
+    }
+
+    pub fn set_cursor_mode(&self, mode: CursorMode) {
+        unsafe { glfwSetInputMode(self.handle, GLFWCursor, mode.raw()) }
+    }
+
+    pub fn raw_mouse_motion_supported(&self) -> bool {
+        unsafe { glfwRawMouseMotionSupported() != False }
+    }
+
+    /// Raw motion only applies while the cursor is disabled.
+    pub fn set_raw_mouse_motion(&self, enabled: bool) -> Result<()> {
+        if enabled && !self.raw_mouse_motion_supported() {
+            return Err(GearError::InvalidArgument("raw mouse motion is not supported on this system".into()));
+        }
+
+        unsafe { glfwSetInputMode(self.handle, GLFWRawMouseMotion, glfw_bool(enabled)) };
+        Ok(())
+    }
+
+    pub fn set_cursor(&self, cursor: Option<&Cursor>) {
+        unsafe { glfwSetCursor(self.handle, cursor.map_or(null_mut(), Cursor::handle)) }
+    }
+
+    /// Drains everything queued since the last call. Only the events of this window are returned.
+    pub fn events(&self) -> vec_deque::IntoIter<Event> {
+        self.state.events.take().into_iter()
+    }
+
+    pub fn next_event(&self) -> Option<Event> {
+        self.state.events.try_borrow_mut().ok()?.pop_front()
+    }
+
+    /// Also applies a pending framebuffer resize to the viewport, so the callback itself never
+    /// has to touch GL.
+    pub fn frame_begin(&self) -> bool {
+        self.make_current();
+        self.poll_events();
+
+        if let Some((width, height)) = self.state.framebuffer_size.take() {
+            if let Err(error) = self.context().gl_unshared().and_then(|gl| unsafe { gl.Viewport(0, 0, width, height) }) {
+                set_last_error(&error);
+            }
+        }
+
+        !self.should_close()
+    }
+
+    pub fn frame_end(&self) {
+        self.swap_buffers();
+    }
+
+    pub fn run_with<F: FnMut(&mut Frame) -> Result<()>>(&self, mut f: F) -> Result<()> {
+        let start = unsafe { glfwGetTime() };
+        let mut last = start;
+        let mut index = 0;
+
+        while self.frame_begin() {
+            let now = unsafe { glfwGetTime() };
+            let mut frame = Frame {
+                window: self,
+                index,
+                time: now - start,
+                delta: now - last,
+            };
+
+            f(&mut frame)?;
+
+            self.frame_end();
+            last = now;
+            index += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        self.context().invalidate();
+        GEAR_WINDOWS.with(|windows| windows.borrow_mut().retain(|window| *window != self.handle));
+
+        unsafe {
+            glfwDestroyWindow(self.handle);
+            release_glfw();
+        }
+    }
+}
+
+pub(crate) fn retain_glfw() {
+    GLFW_USERS.fetch_add(1, Ordering::SeqCst);
+}
+
+pub(crate) unsafe fn release_glfw() {
+    if GLFW_USERS.fetch_sub(1, Ordering::SeqCst) == 1 {
+        glfwTerminate();
+    }
+}
+
+unsafe fn window_state<'a>(window: *mut GLFWwindow) -> Option<&'a WindowState> {
+    if window.is_null() {
+        return None;
+    }
+    (glfwGetWindowUserPointer(window) as *const WindowState).as_ref()
+}
+
+extern "C" fn window_size_callback(window: *mut GLFWwindow, width: c_int, height: c_int) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.push(Event::Resized { width, height });
+    }
+}
+
+extern "C" fn framebuffer_size_callback(window: *mut GLFWwindow, width: c_int, height: c_int) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.framebuffer_size.set(Some((width, height)));
+        state.push(Event::FramebufferResized { width, height });
+    }
+}
+
+extern "C" fn key_callback(window: *mut GLFWwindow, key: c_int, scancode: c_int, action: c_int, mods: c_int) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.push(Event::Key {
+            key: Key::from_raw(key),
+            scancode,
+            action: Action::from_raw(action),
+            mods: Modifiers::from_raw(mods),
+        });
+    }
+}
+
+extern "C" fn char_callback(window: *mut GLFWwindow, codepoint: c_uint) {
+    if let (Some(state), Some(character)) = (unsafe { window_state(window) }, char::from_u32(codepoint)) {
+        state.push(Event::Char(character));
+    }
+}
+
+extern "C" fn cursor_pos_callback(window: *mut GLFWwindow, x: c_double, y: c_double) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.push(Event::CursorMoved { x, y });
+    }
+}
+
+extern "C" fn cursor_enter_callback(window: *mut GLFWwindow, entered: c_int) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.push(Event::CursorEntered(entered != False));
+    }
+}
+
+extern "C" fn mouse_button_callback(window: *mut GLFWwindow, button: c_int, action: c_int, mods: c_int) {
+    if let (Some(state), Some(button)) = (unsafe { window_state(window) }, MouseButton::from_raw(button)) {
+        state.push(Event::MouseButton {
+            button,
+            action: Action::from_raw(action),
+            mods: Modifiers::from_raw(mods),
+        });
+    }
+}
+
+extern "C" fn scroll_callback(window: *mut GLFWwindow, x: c_double, y: c_double) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.push(Event::Scroll { x, y });
+    }
+}
+
+extern "C" fn window_focus_callback(window: *mut GLFWwindow, focused: c_int) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.push(Event::Focused(focused != False));
+    }
+}
+
+extern "C" fn window_iconify_callback(window: *mut GLFWwindow, iconified: c_int) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.push(Event::Iconified(iconified != False));
+    }
+}
+
+// GLFW still sets the close flag; the event only tells the caller it was the user asking.
+extern "C" fn window_close_callback(window: *mut GLFWwindow) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.push(Event::CloseRequested);
+    }
+}
+
+extern "C" fn drop_callback(window: *mut GLFWwindow, count: c_int, paths: *mut *const c_char) {
+    let Some(state) = (unsafe { window_state(window) }) else { return };
+    if paths.is_null() {
+        return;
+    }
+
+    let paths = unsafe { slice::from_raw_parts(paths, count.max(0) as usize) }
+        .iter()
+        .filter(|path| !path.is_null())
+        .map(|path| PathBuf::from(unsafe { CStr::from_ptr(*path) }.to_string_lossy().into_owned()))
+        .collect();
+
+    state.push(Event::FileDrop(paths));
+}
+
+extern "C" fn content_scale_callback(window: *mut GLFWwindow, x: c_float, y: c_float) {
+    if let Some(state) = unsafe { window_state(window) } {
+        state.push(Event::ContentScale { x, y });
+    }
+}
+
+/// The frame being drawn in `Window::run_with`.
+pub struct Frame<'a> {
+    window: &'a Window,
+    pub index: u64,
+    pub time: f64,
+    pub delta: f64,
+}
+
+impl Frame<'_> {
+    pub fn window(&self) -> &Window {
+        self.window
+    }
+
+    pub fn close(&self) {
+        self.window.set_should_close(true);
+    }
+}
+
+pub(crate) fn framebuffer_size(window: *mut GLFWwindow) -> (c_int, c_int) {
+    let (mut width, mut height) = (0, 0);
+    unsafe { glfwGetFramebufferSize(window, &mut width, &mut height) };
+    (width, height)
+}
+
+fn glfw_bool(value: bool) -> c_int {
+    if value { True } else { False }
+}
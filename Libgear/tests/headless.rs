@@ -0,0 +1,26 @@
+#![cfg(feature = "osmesa")]
+use gear::{gl, *};
+
+#[test]
+#[ignore = "needs libOSMesa at runtime"]
+fn headless_window_reads_back_what_was_drawn() {
+    let window = Window::new(&WindowConfig::new("headless").size(4, 4).headless()).unwrap();
+    let context = window.context();
+    assert_eq!(window.framebuffer_size(), (4, 4));
+
+    // Red everywhere, then green in the top half only.
+    context.clear(BufferMask::COLOR, [1.0, 0.0, 0.0, 1.0]).unwrap();
+    unsafe {
+        let gl = context.gl_unshared().unwrap();
+        gl.Enable(gl::SCISSOR_TEST).unwrap();
+        gl.Scissor(0, 2, 4, 2).unwrap();
+    }
+    context.clear(BufferMask::COLOR, [0.0, 1.0, 0.0, 1.0]).unwrap();
+    unsafe { context.gl_unshared().unwrap().Disable(gl::SCISSOR_TEST).unwrap() };
+
+    let image = window.read_pixels().unwrap();
+    assert_eq!((image.width(), image.height()), (4, 4));
+    let (top, bottom) = image.pixels().split_at(4 * 4 * 2);
+    assert!(top.chunks(4).all(|pixel| pixel == [0, 255, 0, 255]));
+    assert!(bottom.chunks(4).all(|pixel| pixel == [255, 0, 0, 255]));
+}
@@ -0,0 +1,373 @@
+#![cfg(feature = "mock")]
+use std::{cell::RefCell, rc::Rc};
+
+use gear::{gl::{self, types::GLint}, mock::{self, MockArg}, *};
+
+const VERTEX: &str = "#version 330 core\nvoid main() { gl_Position = vec4(0.0); }";
+const FRAGMENT: &str = "#version 330 core\nout vec4 color;\nvoid main() { color = vec4(1.0); }";
+
+fn program(context: &GlContext) -> ShaderProgram {
+    ShaderProgram::builder().vertex(VERTEX).fragment(FRAGMENT).build(context).unwrap()
+}
+
+fn int(call: &mock::Call, index: usize) -> i64 {
+    call.arg(index).and_then(|arg| arg.as_int()).unwrap()
+}
+
+#[test]
+fn buffer_data_is_sized_in_bytes() {
+    let context = mock::context();
+    let buffer = Buffer::from_slice(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &[0.0f32; 6]).unwrap();
+
+    assert_ne!(buffer.name(), 0);
+    let calls = mock::calls_to("glBufferData");
+    assert_eq!(calls.len(), 1);
+    assert_eq!(int(&calls[0], 0), gl::ARRAY_BUFFER as i64);
+    assert_eq!(int(&calls[0], 1), 24);
+    assert_eq!(int(&calls[0], 3), gl::STATIC_DRAW as i64);
+}
+
+#[test]
+fn buffer_update_is_bounds_checked() {
+    let context = mock::context();
+    let mut buffer = Buffer::from_slice(&context, BufferTarget::ArrayBuffer, BufferUsage::DynamicDraw, &[0u32; 4]).unwrap();
+
+    buffer.update(1, &[1, 2]).unwrap();
+    assert_eq!(int(&mock::calls_to("glBufferSubData")[0], 1), 4);
+
+    assert!(buffer.update(3, &[1, 2]).is_err());
+    assert!(buffer.update(usize::MAX, &[1]).is_err());
+    assert_eq!(mock::calls_to("glBufferSubData").len(), 1);
+}
+
+#[test]
+fn objects_get_distinct_names() {
+    let context = mock::context();
+    let a = Buffer::<u8>::new(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw).unwrap();
+    let b = Buffer::<u8>::new(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw).unwrap();
+    let vertex_array = VertexArray::new(&context).unwrap();
+
+    assert_ne!(a.name(), 0);
+    assert_ne!(a.name(), b.name());
+    assert_ne!(vertex_array.name(), 0);
+}
+
+#[test]
+fn shader_program_compiles_and_links() {
+    let context = mock::context();
+    let program = program(&context);
+
+    assert_ne!(program.name(), 0);
+    assert_eq!(mock::calls_to("glCompileShader").len(), 2);
+    assert_eq!(mock::calls_to("glLinkProgram").len(), 1);
+    // The stages are deleted once the program is linked.
+    assert_eq!(mock::calls_to("glDeleteShader").len(), 2);
+}
+
+#[test]
+fn shader_compile_errors_are_reported() {
+    let context = mock::context();
+    mock::on("glGetShaderiv", |_| 0);
+
+    let error = ShaderProgram::builder().vertex(VERTEX).fragment(FRAGMENT).build(&context).err().unwrap();
+    assert!(matches!(error, GearError::ShaderCompile { stage: ShaderStage::Vertex, .. }));
+    assert!(mock::calls_to("glLinkProgram").is_empty());
+}
+
+#[test]
+fn vertex_array_describes_attributes() {
+    let context = mock::context();
+    let buffer = Buffer::from_slice(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &[[0.0f32; 5]; 3]).unwrap();
+    let layout = VertexLayout::new()
+        .attribute(0, 3, AttribType::Float, false)
+        .attribute(1, 2, AttribType::Float, false);
+
+    let mut vertex_array = VertexArray::new(&context).unwrap();
+    vertex_array.bind_buffer(&buffer, &layout).unwrap();
+
+    assert_eq!(vertex_array.vertex_count(), 3);
+    let pointers = mock::calls_to("glVertexAttribPointer");
+    assert_eq!(pointers.len(), 2);
+    assert_eq!((int(&pointers[1], 0), int(&pointers[1], 1), int(&pointers[1], 4)), (1, 2, 20));
+    assert_eq!(pointers[1].arg(5).and_then(|arg| arg.as_pointer()), Some(12));
+    assert_eq!(mock::calls_to("glEnableVertexAttribArray").len(), 2);
+}
+
+#[test]
+fn vertex_array_is_unbound_after_setup() {
+    let context = mock::context();
+    let vertices = Buffer::from_slice(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &[[0.0f32; 2]; 3]).unwrap();
+    let indices = Buffer::from_slice(&context, BufferTarget::ElementArrayBuffer, BufferUsage::StaticDraw, &[0u16, 1, 2]).unwrap();
+
+    let mut vertex_array = VertexArray::new(&context).unwrap();
+    vertex_array.bind_buffer(&vertices, &VertexLayout::new().attribute(0, 2, AttribType::Float, false)).unwrap();
+    assert_eq!(int(mock::calls_to("glBindVertexArray").last().unwrap(), 0), 0);
+
+    vertex_array.bind_index_buffer(&indices).unwrap();
+    assert_eq!(int(mock::calls_to("glBindVertexArray").last().unwrap(), 0), 0);
+    assert_eq!(vertex_array.index_count(), 3);
+}
+
+#[test]
+fn vertex_counts_follow_the_attached_buffers() {
+    let context = mock::context();
+    let mut positions = Buffer::from_slice(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &[[0.0f32; 2]; 100]).unwrap();
+    let colors = Buffer::from_slice(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &[[0u8; 4]; 200]).unwrap();
+    let offsets = Buffer::from_slice(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &[[0.0f32; 2]; 10]).unwrap();
+    let mut indices = Buffer::from_slice(&context, BufferTarget::ElementArrayBuffer, BufferUsage::StaticDraw, &[0u16, 1, 2]).unwrap();
+
+    let mut vertex_array = VertexArray::new(&context).unwrap();
+    vertex_array.bind_buffer(&positions, &VertexLayout::new().attribute(0, 2, AttribType::Float, false)).unwrap();
+    vertex_array.bind_buffer(&colors, &VertexLayout::new().attribute(1, 4, AttribType::UnsignedByte, true)).unwrap();
+    vertex_array.bind_buffer(&offsets, &VertexLayout::new().attribute(2, 2, AttribType::Float, false).divisor(1)).unwrap();
+    vertex_array.bind_index_buffer(&indices).unwrap();
+    assert_eq!(vertex_array.vertex_count(), 100);
+
+    positions.upload(&[[0.0f32; 2]; 300]).unwrap();
+    indices.upload(&[0u16; 6]).unwrap();
+    assert_eq!((vertex_array.vertex_count(), vertex_array.index_count()), (200, 6));
+
+    vertex_array.bind_buffer(&offsets, &VertexLayout::new().attribute(1, 2, AttribType::Float, false)).unwrap();
+    assert_eq!(vertex_array.vertex_count(), 10);
+}
+
+#[test]
+fn draws_use_the_program_and_vertex_array() {
+    let context = mock::context();
+    let program = program(&context);
+    let vertices = Buffer::from_slice(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &[[0.0f32; 2]; 4]).unwrap();
+    let indices = Buffer::from_slice(&context, BufferTarget::ElementArrayBuffer, BufferUsage::StaticDraw, &[0u16, 1, 2, 2, 3, 0]).unwrap();
+
+    let mut vertex_array = VertexArray::new(&context).unwrap();
+    vertex_array.bind_buffer(&vertices, &VertexLayout::new().attribute(0, 2, AttribType::Float, false)).unwrap();
+    vertex_array.bind_index_buffer(&indices).unwrap();
+    mock::take_calls();
+
+    draw_arrays(&program, &vertex_array, Primitive::Triangles, 1..4).unwrap();
+    let names: Vec<_> = mock::calls().iter().map(|call| call.name).filter(|name| *name != "glGetError").collect();
+    assert_eq!(names, ["glUseProgram", "glBindVertexArray", "glDrawArrays", "glBindVertexArray"]);
+    let draw = &mock::calls_to("glDrawArrays")[0];
+    assert_eq!((int(draw, 0), int(draw, 1), int(draw, 2)), (gl::TRIANGLES as i64, 1, 3));
+
+    draw_elements_instanced(&program, &vertex_array, Primitive::Triangles, 3.., 2).unwrap();
+    let draw = &mock::calls_to("glDrawElementsInstanced")[0];
+    assert_eq!((int(draw, 1), int(draw, 2), int(draw, 4)), (3, gl::UNSIGNED_SHORT as i64, 2));
+    assert_eq!(draw.arg(3), Some(MockArg::Pointer(6)));
+
+    assert!(draw_arrays(&program, &vertex_array, Primitive::Triangles, 0..5).is_err());
+}
+
+#[test]
+fn index_uploads_after_a_draw_leave_the_vertex_array_alone() {
+    let context = mock::context();
+    let program = program(&context);
+    let vertices = Buffer::from_slice(&context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &[[0.0f32; 2]; 3]).unwrap();
+    let indices = Buffer::from_slice(&context, BufferTarget::ElementArrayBuffer, BufferUsage::StaticDraw, &[0u16, 1, 2]).unwrap();
+
+    let mut vertex_array = VertexArray::new(&context).unwrap();
+    vertex_array.bind_buffer(&vertices, &VertexLayout::new().attribute(0, 2, AttribType::Float, false)).unwrap();
+    vertex_array.bind_index_buffer(&indices).unwrap();
+    mock::take_calls();
+
+    draw_elements(&program, &vertex_array, Primitive::Triangles, ..).unwrap();
+    let _other = Buffer::from_slice(&context, BufferTarget::ElementArrayBuffer, BufferUsage::StaticDraw, &[0u16; 6]).unwrap();
+
+    let mut bound_vertex_array = 0;
+    for call in mock::calls() {
+        match call.name {
+            "glBindVertexArray" => bound_vertex_array = int(&call, 0),
+            "glBindBuffer" if int(&call, 0) == gl::ELEMENT_ARRAY_BUFFER as i64 && int(&call, 1) != 0 => {
+                assert_eq!(bound_vertex_array, 0, "index buffer bound into vertex array {}", bound_vertex_array);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn windowless_framebuffers_have_no_window_to_return_to() {
+    let context = mock::context();
+    let framebuffer = Framebuffer::new(&context, 4, 4).unwrap();
+    framebuffer.bind().unwrap();
+    mock::take_calls();
+
+    framebuffer.unbind().unwrap();
+    assert_eq!(int(mock::calls_to("glBindFramebuffer").last().unwrap(), 1), 0);
+    assert!(mock::calls_to("glViewport").is_empty());
+    assert!(matches!(framebuffer.blit(None, BufferMask::COLOR, MagFilter::Nearest), Err(GearError::InvalidArgument(_))));
+}
+
+#[test]
+fn images_are_uploaded_bottom_row_first() {
+    let context = mock::context();
+    // Handlers run inside the GL call, so the pixels are copied out instead of checked there.
+    let uploaded = Rc::new(RefCell::new(Vec::new()));
+    let sink = uploaded.clone();
+    mock::on("glTexImage2D", move |args| {
+        if let Some(MockArg::Pointer(pixels)) = args.get(8).copied().filter(|arg| *arg != MockArg::Pointer(0)) {
+            sink.borrow_mut().extend_from_slice(unsafe { std::slice::from_raw_parts(pixels as *const u8, 2) });
+        }
+        0
+    });
+
+    let image = Image::new(1, 2, PixelFormat::R8, vec![1, 2]).unwrap();
+    Texture2D::from_image(&context, &image).unwrap();
+    assert_eq!(*uploaded.borrow(), [2, 1]);
+}
+
+#[test]
+fn texture_setup_leaves_bound_units_alone() {
+    let context = mock::context();
+    let mut a = Texture2D::new(&context, 2, 2, PixelFormat::Rgba8).unwrap();
+    let b = Texture2D::new(&context, 2, 2, PixelFormat::Rgba8).unwrap();
+    a.bind(0).unwrap();
+    b.bind(1).unwrap();
+    mock::take_calls();
+
+    a.set_wrap(WrapMode::ClampToEdge, WrapMode::ClampToEdge).unwrap();
+    let active = mock::calls_to("glActiveTexture");
+    assert!(!active.is_empty());
+    assert!(active.iter().all(|call| int(call, 0) == (gl::TEXTURE0 + SETUP_TEXTURE_UNIT) as i64));
+
+    assert!(a.bind(SETUP_TEXTURE_UNIT).is_err());
+}
+
+// Tracks the draw and read framebuffer bindings the way GL does, so they can be queried back.
+fn track_framebuffer_bindings() -> Rc<RefCell<(i64, i64)>> {
+    let bound = Rc::new(RefCell::new((0, 0)));
+    let binds = bound.clone();
+    mock::on("glBindFramebuffer", move |args| {
+        let (target, name) = (args[0].as_int().unwrap(), args[1].as_int().unwrap());
+        let mut bound = binds.borrow_mut();
+        if target != gl::READ_FRAMEBUFFER as i64 {
+            bound.0 = name;
+        }
+        if target != gl::DRAW_FRAMEBUFFER as i64 {
+            bound.1 = name;
+        }
+        0
+    });
+    let queries = bound.clone();
+    mock::on("glGetIntegerv", move |args| {
+        let value = match args[0].as_int().unwrap() as u32 {
+            gl::DRAW_FRAMEBUFFER_BINDING => queries.borrow().0,
+            gl::READ_FRAMEBUFFER_BINDING => queries.borrow().1,
+            _ => return 0,
+        };
+        unsafe { *(args[1].as_pointer().unwrap() as *mut GLint) = value as GLint };
+        0
+    });
+    bound
+}
+
+#[test]
+fn framebuffer_setup_restores_the_previous_bindings() {
+    let context = mock::context();
+    let bound = track_framebuffer_bindings();
+    let texture = Texture2D::new(&context, 4, 4, PixelFormat::Rgba8).unwrap();
+    let depth = Renderbuffer::new(&context, 4, 4, RenderbufferFormat::Depth24).unwrap();
+    let mut framebuffer = Framebuffer::new(&context, 4, 4).unwrap();
+    let mut other = Framebuffer::new(&context, 4, 4).unwrap();
+    other.attach_texture(Attachment::Color(0), &texture).unwrap();
+    assert_eq!(*bound.borrow(), (0, 0));
+
+    other.bind().unwrap();
+    let expected = (other.name() as i64, other.name() as i64);
+    framebuffer.attach_texture(Attachment::Color(0), &texture).unwrap();
+    assert_eq!(*bound.borrow(), expected);
+    framebuffer.attach_renderbuffer(Attachment::Depth, &depth).unwrap();
+    assert_eq!(*bound.borrow(), expected);
+    framebuffer.check().unwrap();
+    assert_eq!(*bound.borrow(), expected);
+    framebuffer.blit(Some(&other), BufferMask::COLOR, MagFilter::Nearest).unwrap();
+    assert_eq!(*bound.borrow(), expected);
+    framebuffer.read_pixels(0).unwrap();
+    assert_eq!(*bound.borrow(), expected);
+
+    assert!(framebuffer.attach_renderbuffer(Attachment::Color(1), &depth).is_err());
+    assert_eq!(*bound.borrow(), expected);
+}
+
+#[test]
+fn draw_buffers_follow_attachment_numbers() {
+    let context = mock::context();
+    let draw_buffers = Rc::new(RefCell::new(Vec::new()));
+    let sink = draw_buffers.clone();
+    mock::on("glDrawBuffers", move |args| {
+        if let (Some(count), Some(MockArg::Pointer(buffers))) = (args[0].as_int(), args.get(1).copied()) {
+            let buffers = unsafe { std::slice::from_raw_parts(buffers as *const u32, count as usize) };
+            *sink.borrow_mut() = buffers.to_vec();
+        }
+        0
+    });
+
+    let a = Texture2D::new(&context, 4, 4, PixelFormat::Rgba8).unwrap();
+    let b = Texture2D::new(&context, 4, 4, PixelFormat::Rgba8).unwrap();
+    let mut framebuffer = Framebuffer::new(&context, 4, 4).unwrap();
+    framebuffer.attach_texture(Attachment::Color(0), &a).unwrap();
+    framebuffer.attach_texture(Attachment::Color(2), &b).unwrap();
+
+    assert_eq!(*draw_buffers.borrow(), [gl::COLOR_ATTACHMENT0, gl::NONE, gl::COLOR_ATTACHMENT2]);
+}
+
+#[test]
+fn read_pixels_come_back_top_row_first() {
+    let context = mock::context();
+    // GL returns the bottom row first; here it is all 1s and the top row all 2s.
+    mock::on("glReadPixels", |args| {
+        if let Some(MockArg::Pointer(pixels)) = args.get(6).copied() {
+            let pixels = unsafe { std::slice::from_raw_parts_mut(pixels as *mut u8, 8) };
+            pixels[..4].fill(1);
+            pixels[4..].fill(2);
+        }
+        0
+    });
+
+    let texture = Texture2D::new(&context, 1, 2, PixelFormat::Rgba8).unwrap();
+    let mut framebuffer = Framebuffer::new(&context, 1, 2).unwrap();
+    framebuffer.attach_texture(Attachment::Color(1), &texture).unwrap();
+    mock::take_calls();
+
+    let image = framebuffer.read_pixels(1).unwrap();
+    assert_eq!((image.width(), image.height(), image.format()), (1, 2, PixelFormat::Rgba8));
+    assert_eq!(image.pixels(), [2, 2, 2, 2, 1, 1, 1, 1]);
+
+    let calls: Vec<_> = mock::calls().into_iter().filter(|call| call.name != "glGetError").collect();
+    let position = |name: &str| calls.iter().position(|call| call.name == name).unwrap();
+    let (store, read_buffer, read) = (position("glPixelStorei"), position("glReadBuffer"), position("glReadPixels"));
+    assert!(store < read && read_buffer < read);
+    assert_eq!((int(&calls[store], 0), int(&calls[store], 1)), (gl::PACK_ALIGNMENT as i64, 1));
+    assert_eq!(int(&calls[read_buffer], 0), gl::COLOR_ATTACHMENT1 as i64);
+    let bind = calls[..read].iter().rposition(|call| call.name == "glBindFramebuffer").unwrap();
+    assert_eq!(int(&calls[bind], 0), gl::READ_FRAMEBUFFER as i64);
+    assert_eq!(int(&calls[bind], 1), framebuffer.name() as i64);
+}
+
+#[test]
+fn sub_images_are_bounds_checked() {
+    let context = mock::context();
+    let mut texture = Texture2D::new(&context, 4, 4, PixelFormat::R8).unwrap();
+
+    texture.sub_image(2, 2, 2, 2, &[0u8; 4]).unwrap();
+    assert!(texture.sub_image(3, 0, 2, 1, &[0u8; 2]).is_err());
+    assert!(texture.sub_image(GLint::MAX, 0, 2, 1, &[0u8; 2]).is_err());
+    assert_eq!(mock::calls_to("glTexSubImage2D").len(), 1);
+}
+
+#[test]
+fn clear_and_viewport() {
+    let context = mock::context();
+    context.clear(BufferMask::COLOR | BufferMask::DEPTH, [0.2, 0.3, 0.3, 1.0]).unwrap();
+    context.set_viewport(0, 0, 640, 480).unwrap();
+
+    let clear_color = &mock::calls_to("glClearColor")[0];
+    assert_eq!(clear_color.arg(1).and_then(|arg| arg.as_float()), Some(0.3f32 as f64));
+    let clear = &mock::calls_to("glClear")[0];
+    assert_eq!(int(clear, 0), (gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT) as i64);
+    let viewport = &mock::calls_to("glViewport")[0];
+    assert_eq!((int(viewport, 2), int(viewport, 3)), (640, 480));
+
+    context.clear(BufferMask::DEPTH, [0.0; 4]).unwrap();
+    assert_eq!(mock::calls_to("glClearColor").len(), 1);
+    assert!(context.set_viewport(0, 0, -1, 480).is_err());
+}
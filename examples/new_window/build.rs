@@ -1,7 +0,0 @@
-#[allow(non_snake_case)]
-fn main() {
-    let outDir = std::env::var("OUT_DIR").unwrap();
-
-    println!("cargo:rustc-link-search=native={}", outDir);
-    println!("cargo:rustc-link-lib=dylib=gear");
-}
\ No newline at end of file
@@ -1,13 +1,59 @@
-use std::ffi::*;
+use gear::{
+    Action, AttribType, Buffer, BufferMask, BufferTarget, BufferUsage, Event, Key, Primitive, ShaderProgram,
+    VertexArray, VertexLayout, Window, WindowConfig,
+};
 
-extern {
-    fn run(title: *const c_char);
-}
+const VERTEX: &str = r"
+#version 330 core
+layout (location = 0) in vec3 aPos;
+
+void main()
+{
+    gl_Position = vec4(aPos, 1.0);
+}";
+
+const FRAGMENT: &str = r"
+#version 330 core
+out vec4 FragColor;
+
+uniform vec4 color;
+
+void main()
+{
+    FragColor = color;
+}";
 
 fn main() {
-    unsafe {
-        let title = CString::new("Window").unwrap();
-        println!("{:#?} {:#?}", title, title.as_ptr());
-        run(title.as_ptr());
+    if let Err(error) = run() {
+        eprintln!("{}", error);
     }
-}
\ No newline at end of file
+}
+
+fn run() -> gear::Result<()> {
+    let window = Window::new(&WindowConfig::new("Window"))?;
+    let context = window.context();
+
+    let vertices: [f32; 9] = [
+        -0.5, -0.5, 0.,
+        0.5, 0.5, 0.,
+        0., 0.5, 0.
+    ];
+
+    let program = ShaderProgram::builder().vertex(VERTEX).fragment(FRAGMENT).build(context)?;
+    program.set_uniform("color", [1.0, 0.5, 0.2, 1.0])?;
+
+    let vbo = Buffer::from_slice(context, BufferTarget::ArrayBuffer, BufferUsage::StaticDraw, &vertices)?;
+    let mut vao = VertexArray::new(context)?;
+    vao.bind_buffer(&vbo, &VertexLayout::new().attribute(0, 3, AttribType::Float, false))?;
+
+    window.run_with(|frame| {
+        for event in frame.window().events() {
+            if let Event::Key { key: Key::Escape, action: Action::Press, .. } = event {
+                frame.close();
+            }
+        }
+
+        context.clear(BufferMask::COLOR, [0.2, 0.3, 0.3, 1.0])?;
+        gear::draw_arrays(&program, &vao, Primitive::Triangles, ..)
+    })
+}